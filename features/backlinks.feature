@@ -0,0 +1,48 @@
+Feature: list the documents linking to a file
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Two](sub/2.md)
+      """
+    And file "sub/2.md" with content:
+      """
+      # Two
+
+      ### section
+
+      [One](../1.md)
+      ![image](../image.png)
+      """
+    And file "3.md" with content:
+      """
+      # Three
+
+      [Two](sub/2.md#section)
+      [One](1.md)
+      """
+    And file "image.png"
+
+  Scenario: document
+    When listing the backlinks of "sub/2.md"
+    Then it prints:
+      """
+      1.md:3  references "sub/2.md"
+      3.md:3  references "sub/2.md#section"
+      """
+    And the exit code is 0
+
+  Scenario: resource
+    When listing the backlinks of "image.png"
+    Then it prints:
+      """
+      sub/2.md:6  references "../image.png"
+      """
+    And the exit code is 0
+
+  Scenario: no backlinks
+    When listing the backlinks of "zonk.md"
+    Then it prints nothing
+    And the exit code is 0
@@ -0,0 +1,69 @@
+Feature: links in generated backlinks sections don't count as backlinks
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "backlinksSection": "### backlinks"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      [Two](2.md)
+      [Three](3.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      text
+
+      ### backlinks
+
+      - [One](1.md)
+      - [Three](3.md)
+      """
+    And file "3.md" with content:
+      """
+      # Three
+
+      text
+      """
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      3.md:5  added 1.md to backlinks section
+      2.md:8  removed stale backlink to 3.md
+      """
+    And file "1.md" is unchanged
+    And file "2.md" should contain:
+      """
+      # Two
+
+      text
+
+      ### backlinks
+
+      - [One](1.md)
+      """
+    And file "3.md" should contain:
+      """
+      # Three
+
+      text
+
+      ### backlinks
+
+      - [One](1.md)
+      """
+    And the exit code is 0
+
+  Scenario: fix and check
+    When fixing
+    And checking
+    Then it finds no issues
@@ -0,0 +1,48 @@
+Feature: generated backlinks to files with spaces in their name
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "backlinksSection": "### backlinks"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      text
+      """
+    And file "two words.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  added two words.md to backlinks section
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      text
+
+      ### backlinks
+
+      - [Two](two%20words.md)
+      """
+    And the exit code is 0
+
+  Scenario: fix and check
+    When fixing
+    And checking
+    Then it prints:
+      """
+      """
+    And the exit code is 0
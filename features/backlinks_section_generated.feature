@@ -0,0 +1,65 @@
+Feature: maintain a generated backlinks section
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "backlinksSection": "### backlinks"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      text
+
+      ### backlinks
+
+      - [Old](old.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      [One again](1.md)
+      """
+    And file "sub/4.md" with content:
+      """
+      # Four
+
+      [One](../1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:7  link to non-existing file "old.md"
+      1.md:7  missing link to 2.md
+      1.md:7  missing link to 2.md
+      1.md:7  missing link to sub/4.md
+      1.md:7  stale backlink to old.md
+      """
+    And the exit code is 5
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  added 2.md to backlinks section
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      text
+
+      ### backlinks
+
+      - [Two](2.md)
+      - [Four](sub/4.md)
+      """
+    And file "2.md" is unchanged
+    And the exit code is 0
@@ -0,0 +1,62 @@
+Feature: remove stale entries from the backlinks section
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "backlinksSection": "### backlinks"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      text
+
+      ### backlinks
+
+      - [Two](2.md)
+      - [Three](3.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      [Three](3.md)
+      """
+    And file "3.md" with content:
+      """
+      # Three
+
+      [One](1.md)
+      [Two](2.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:7  stale backlink to 2.md
+      """
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:7  removed stale backlink to 2.md
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      text
+
+      ### backlinks
+
+      - [Three](3.md)
+      """
+    And file "2.md" is unchanged
+    And file "3.md" is unchanged
+    And the exit code is 0
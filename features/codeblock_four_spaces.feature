@@ -0,0 +1,21 @@
+Feature: codeblock indented by four spaces
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Title
+      [Two](2.md)
+
+          result := map[^0]
+          ### not a section
+          [Zonk](zonk.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it finds no issues
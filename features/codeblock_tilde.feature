@@ -0,0 +1,26 @@
+Feature: codeblock with tilde fences
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Title
+      [Two](2.md)
+
+      ~~~go
+      result := map[^0]
+      ~~~
+
+      ~~~md
+      ### not a section
+      [Zonk](zonk.md)
+      ~~~
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it finds no issues
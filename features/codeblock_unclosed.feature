@@ -0,0 +1,39 @@
+Feature: codeblock without closing fence
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Title
+      text
+
+      ~~~~md
+      ### not a section
+      ~~~
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:4  unclosed fence
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:4  unclosed fence
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:4  unclosed fence
+      """
+    And all files are unchanged
+    And the exit code is 1
@@ -0,0 +1,29 @@
+Feature: Markdown syntax follows CommonMark
+
+  Background:
+    Given file "1.md" with content:
+      """
+      One
+      ===
+
+      #hashtag is not a section
+
+      the code `[Zonk](zonk.md)` contains no link
+      the code ``map[`^0`] and [Zonk](zonk.md)`` contains no footnote or link
+      [Two](two_(2).md)
+
+      ```md
+      # not a section
+      [Zonk](zonk.md)
+      ```
+      """
+    And file "two_(2).md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it finds no issues
@@ -0,0 +1,72 @@
+Feature: documents that aren't valid UTF-8
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Café](cafe.md)
+      """
+    And file "cafe.md" with Latin-1 content:
+      """
+      # Café
+
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      cafe.md:1  file is not valid UTF-8, reading it as Windows-1252
+      """
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      cafe.md:1  converted file from Windows-1252 to UTF-8
+      """
+    And file "cafe.md" should contain:
+      """
+      # Café
+
+      [One](1.md)
+      """
+    And the exit code is 0
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      cafe.md:1  converted file from Windows-1252 to UTF-8
+      """
+    And file "cafe.md" should contain:
+      """
+      # Café
+
+      [One](1.md)
+      """
+    And the exit code is 0
+
+  Scenario: fixing other issues keeps the encoding
+    Given file "cafe.md" with Latin-1 content:
+      """
+      # Café
+
+      [One](1.md)
+
+      ### empty
+      """
+    When running "tikibase fix --skip encoding"
+    Then it prints:
+      """
+      cafe.md:5  removed empty section "empty"
+      """
+    And file "cafe.md" should contain Latin-1:
+      """
+      # Café
+
+      [One](1.md)
+      """
@@ -0,0 +1,96 @@
+Feature: export a static HTML website
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Two](sub/2.md#the-details)
+      """
+    And file "sub/2.md" with content:
+      """
+      # Two
+
+      ### The Details
+
+      ![image](../image.png)
+      [One](../1.md)
+      """
+    And file "image.png"
+
+  Scenario: export
+    When exporting HTML into ".site"
+    Then it prints nothing
+    And file ".site/1.html" should contain:
+      """
+      <!DOCTYPE html>
+      <html>
+      <head>
+      <meta charset="utf-8">
+      <title>One</title>
+      </head>
+      <body>
+      <h1 id="one">One</h1>
+      <p><a href="sub/2.html#the-details">Two</a></p>
+      <nav class="backlinks">
+      <h2>Backlinks</h2>
+      <ul>
+      <li><a href="sub/2.html">Two</a></li>
+      </ul>
+      </nav>
+      </body>
+      </html>
+      """
+    And file ".site/sub/2.html" should contain:
+      """
+      <!DOCTYPE html>
+      <html>
+      <head>
+      <meta charset="utf-8">
+      <title>Two</title>
+      </head>
+      <body>
+      <h1 id="two">Two</h1>
+      <h3 id="the-details">The Details</h3>
+      <p><img src="../image.png" alt="image" />
+      <a href="../1.html">One</a></p>
+      <nav class="backlinks">
+      <h2>Backlinks</h2>
+      <ul>
+      <li><a href="../1.html">One</a></li>
+      </ul>
+      </nav>
+      </body>
+      </html>
+      """
+    And file ".site/image.png" should contain:
+      """
+      content
+      """
+    And file ".site/index.html" should contain:
+      """
+      <!DOCTYPE html>
+      <html>
+      <head>
+      <meta charset="utf-8">
+      <title>Index</title>
+      </head>
+      <body>
+      <h1>Index</h1>
+      <ul>
+      <li><a href="1.html">One</a></li>
+      <li><a href="sub/2.html">Two</a></li>
+      </ul>
+      </body>
+      </html>
+      """
+    And file "1.md" is unchanged
+    And the exit code is 0
+
+  Scenario: export into a directory that the Tikibase loads
+    When exporting HTML into "site"
+    Then it prints:
+      """
+      site  the export directory is inside the Tikibase, choose one outside of it or whose name starts with a dot
+      """
+    And the exit code is 1
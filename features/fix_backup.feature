@@ -0,0 +1,42 @@
+Feature: back up files before fixing them
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      ### section 1
+
+      ### section 2
+      [Two](2.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: fix with backups
+    When fixing with backups
+    Then it prints:
+      """
+      1.md:3  removed empty section "section 1"
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 2
+      [Two](2.md)
+      """
+    And the backup of file "1.md" contains:
+      """
+      # One
+
+      ### section 1
+
+      ### section 2
+      [Two](2.md)
+      """
+    And file "2.md" is unchanged
+    And the exit code is 0
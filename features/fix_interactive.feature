@@ -0,0 +1,53 @@
+Feature: approve fixes interactively
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      ### section 1
+
+      ### section 2
+
+      [Two](2.md)
+
+      ### section 3
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: skip one fix and accept another
+    When I run "tikibase fix --interactive" and answer "n, y"
+    Then it succeeds
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 1
+
+      ### section 2
+
+      [Two](2.md)
+      """
+    And file "2.md" is unchanged
+
+  Scenario: accept all fixes of a kind
+    When I run "tikibase fix --interactive" and answer "a"
+    Then it succeeds
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 2
+
+      [Two](2.md)
+      """
+
+  Scenario: no answers
+    When I run "tikibase fix --interactive" and answer ""
+    Then it succeeds
+    And all files are unchanged
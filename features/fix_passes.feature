@@ -0,0 +1,55 @@
+Feature: fix issues revealed by earlier fixes
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      [Two](TWO.md)
+      """
+    And file "two.md" with content:
+      """
+      # Two
+
+      text
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      two.md:1  document is not connected to any other documents
+      1.md:3  link to "TWO.md" has the wrong capitalization, should be "two.md"
+      """
+    And the exit code is 2
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:3  corrected capitalization of link to "two.md"
+      two.md:5  added 1.md to occurrences section
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      [Two](two.md)
+      """
+    And file "two.md" should contain:
+      """
+      # Two
+
+      text
+
+      ### occurrences
+
+      - [One](1.md)
+      """
+    And the exit code is 0
@@ -0,0 +1,87 @@
+Feature: fix only selected issues
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "sections": ["### one", "### two"]
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      ### two
+
+      [Two](2.md)
+
+      ### one
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      ### one
+
+      ### two
+
+      [One](1.md)
+      """
+
+  Scenario: only one rule
+    When running "tikibase fix --only section-order"
+    Then it prints:
+      """
+      1.md:7  fixed section order
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### one
+      ### two
+
+      [Two](2.md)
+      """
+    And file "2.md" is unchanged
+
+  Scenario: skip a rule
+    When running "tikibase fix --skip empty-section"
+    Then it prints:
+      """
+      1.md:7  fixed section order
+      """
+    And file "2.md" is unchanged
+
+  Scenario: only one path
+    When running "tikibase fix 2.md"
+    Then it prints:
+      """
+      2.md:3  removed empty section "one"
+      """
+    And file "1.md" is unchanged
+    And file "2.md" should contain:
+      """
+      # Two
+
+      ### two
+
+      [One](1.md)
+      """
+
+  Scenario: pitstop with a path
+    When running "tikibase p 1.md"
+    Then it prints:
+      """
+      2.md:3  section "one" has no content
+      1.md:7  removed empty section "one"
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### two
+
+      [Two](2.md)
+      """
+    And file "2.md" is unchanged
@@ -0,0 +1,73 @@
+Feature: undo the last fix
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      ### section 1
+
+      ### section 2
+      [Two](2.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: undo a fix
+    When fixing
+    And undoing
+    Then it prints:
+      """
+      1.md  restored the content from before the last fix
+      """
+    And all files are unchanged
+    And the exit code is 0
+
+  Scenario: undo twice
+    When fixing
+    And undoing
+    And undoing
+    Then it prints:
+      """
+      .tikibase/journal  no fix to undo
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: file changed after the fix
+    When fixing
+    And file "1.md" gets changed to:
+      """
+      # One
+
+      ### section 2
+      [Two](2.md)
+
+      ### section 3
+      """
+    And undoing
+    Then it prints:
+      """
+      1.md  file changed since the last fix, not undoing the fix
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 2
+      [Two](2.md)
+
+      ### section 3
+      """
+    And the exit code is 1
+
+  Scenario: nothing to undo
+    When undoing
+    Then it prints:
+      """
+      .tikibase/journal  no fix to undo
+      """
+    And the exit code is 1
@@ -0,0 +1,92 @@
+Feature: put footnotes in order
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "footnoteOrder": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # Title
+      [Two](2.md)
+
+      ### metrics
+      first footnote[^2] and second footnote[^1]
+
+      [^1]: second footnote
+
+      ### links
+
+      [^2]: first footnote
+        with a second line
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:5  footnotes aren't numbered in the order of their first reference
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  renumbered the footnotes in the order of their first reference
+      1.md:7  moved the footnote definitions to the end of the document
+      """
+    And file "1.md" should contain:
+      """
+      # Title
+      [Two](2.md)
+
+      ### metrics
+      first footnote[^1] and second footnote[^2]
+
+      ### links
+
+      [^1]: first footnote
+        with a second line
+      [^2]: second footnote
+      """
+    And the exit code is 0
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:5  renumbered the footnotes in the order of their first reference
+      1.md:7  moved the footnote definitions to the end of the document
+      """
+    And file "1.md" should contain:
+      """
+      # Title
+      [Two](2.md)
+
+      ### metrics
+      first footnote[^1] and second footnote[^2]
+
+      ### links
+
+      [^1]: first footnote
+        with a second line
+      [^2]: second footnote
+      """
+    And the exit code is 0
+
+  Scenario: not configured
+    Given file "tikibase.json" with content:
+      """
+      {}
+      """
+    When checking
+    Then it finds no issues
@@ -0,0 +1,67 @@
+Feature: put footnote definitions before the generated backlinks section
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "backlinksSection": "### backlinks",
+        "footnoteOrder": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      text[^1]
+
+      [^1]: definition
+
+      more text
+
+      ### backlinks
+
+      - [Two](2.md)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:5  footnote definitions aren't at the end of the document in the order of their first reference
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  moved the footnote definitions to the end of the document
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      text[^1]
+
+      more text
+
+      [^1]: definition
+
+      ### backlinks
+
+      - [Two](2.md)
+      """
+    And the exit code is 0
+
+  Scenario: fix and check
+    When fixing
+    And checking
+    Then it finds no issues
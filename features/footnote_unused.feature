@@ -37,14 +37,52 @@ Feature: recognize unused footnote definitions
 
   Scenario: fix
     When fixing
-    Then it finds no issues
-    And all files are unchanged
+    Then it prints:
+      """
+      1.md:16  removed unused footnote [^unused]
+      """
+    And file "1.md" should contain:
+      """
+      # Title
+      [Two](2.md)
+
+      ### metrics
+      existing footnote[^existing]
+
+      ```go
+      result := map[^0]
+      ```
+
+      Another snippet of code that should be ignored: `map[^0]`.
+
+      ### links
+
+      [^existing]: existing footnote
+      """
+    And the exit code is 0
 
   Scenario: pitstop
     When doing a pitstop
     Then it prints:
       """
-      1.md:16  unused footnote [^unused]
+      1.md:16  removed unused footnote [^unused]
       """
-    And all files are unchanged
-    And the exit code is 1
+    And file "1.md" should contain:
+      """
+      # Title
+      [Two](2.md)
+
+      ### metrics
+      existing footnote[^existing]
+
+      ```go
+      result := map[^0]
+      ```
+
+      Another snippet of code that should be ignored: `map[^0]`.
+
+      ### links
+
+      [^existing]: existing footnote
+      """
+    And the exit code is 0
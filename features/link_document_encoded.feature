@@ -0,0 +1,44 @@
+Feature: accept percent-encoded, angle-bracket, and titled link targets
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      [encoded](my%20note.md#details)
+      [angle brackets](<my note.md>)
+      [title](my%20note.md "the note")
+      ![photo](my%20photo.png "a photo")
+      """
+    And file "my note.md" with content:
+      """
+      # My Note
+
+      [one](1.md)
+
+      ### details
+
+      text
+      """
+    And file "my photo.png"
+
+  Scenario: check
+    When checking
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it finds no issues
+    And all files are unchanged
@@ -0,0 +1,49 @@
+Feature: check external links
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Two](2.md)
+      [working](https://example.com/working)
+      [gone](https://example.com/gone)
+      [moved](https://example.com/old)
+      [not checked](https://intranet.example.com/page)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+    And file "tikibase.json" with content:
+      """
+      {
+        "externalLinks": {
+          "deny": ["https://intranet.example.com/"]
+        }
+      }
+      """
+    And file ".tikibase/external-links.json" with content:
+      """
+      {
+        "https://example.com/working": { "checked": 9999999999, "status": "ok" },
+        "https://example.com/gone": { "checked": 9999999999, "status": "broken", "problem": "HTTP 404" },
+        "https://example.com/old": { "checked": 9999999999, "status": "redirected", "target": "https://example.com/new" }
+      }
+      """
+
+  Scenario: check external links
+    When checking external links
+    Then it prints:
+      """
+      1.md:5  broken external link to https://example.com/gone: HTTP 404
+      1.md:6  external link to https://example.com/old permanently redirects to https://example.com/new
+      """
+    And file "1.md" is unchanged
+    And the exit code is 2
+
+  Scenario: check
+    When checking
+    Then it finds no issues
@@ -0,0 +1,44 @@
+Feature: recognize reference-style links without definition
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Title
+      [Two][two] and [Three][three]
+
+      ```md
+      [Four][four]
+      ```
+
+      ### links
+
+      [two]: 2.md
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:2  link definition [three] doesn't exist
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:2  link definition [three] doesn't exist
+      """
+    And all files are unchanged
+    And the exit code is 1
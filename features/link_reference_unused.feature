@@ -0,0 +1,41 @@
+Feature: recognize unused link reference definitions
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Title
+      [Two][two]
+
+      ### links
+
+      [two]: 2.md "the second document"
+      [unused]: 3.md
+      """
+    And file "2.md" with content:
+      """
+      # Two
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:7  unused link definition [unused]
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:7  unused link definition [unused]
+      """
+    And all files are unchanged
+    And the exit code is 1
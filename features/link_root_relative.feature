@@ -0,0 +1,51 @@
+Feature: root-relative links
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true,
+        "basePath": "/wiki"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      [Rust](/wiki/topics/rust.md#details)
+      [blog](/blog/post.md)
+      [missing](/wiki/missing.md)
+      """
+    And file "topics/rust.md" with content:
+      """
+      # Rust
+
+      [One](/wiki/1.md)
+
+      ### details
+
+      text
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:5  link to non-existing file "missing.md"
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:5  link to non-existing file "missing.md"
+      """
+    And all files are unchanged
+    And the exit code is 1
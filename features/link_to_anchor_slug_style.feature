@@ -0,0 +1,31 @@
+Feature: configurable anchor slug style
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "slugStyle": "github"
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+      [GitHub anchor](2.md#whats-new-in-v2_0)
+      [kebab anchor](2.md#what-s-new-in-v2-0)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      ### What's new in v2_0?
+
+      [backlink](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:3  link to non-existing anchor "#what-s-new-in-v2-0" in "2.md"
+      """
+    And the exit code is 1
@@ -0,0 +1,31 @@
+Feature: link to the second of two headings with the same title
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+      [second details](2.md#details-1)
+      [third details](2.md#details-2)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      ### Details
+
+      [backlink](1.md)
+
+      ### Details
+
+      more
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      2.md:3  document contains multiple "Details" sections
+      2.md:7  document contains multiple "Details" sections
+      1.md:3  link to non-existing anchor "#details-2" in "2.md"
+      """
+    And the exit code is 3
@@ -0,0 +1,33 @@
+Feature: link to explicitly defined anchors in another document
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+      [custom heading id](2.md#custom)
+      [HTML id](2.md#html-id)
+      [HTML name](2.md#html-name)
+      """
+    And file "2.md" with content:
+      """
+      # Two
+
+      ### Custom Heading {#custom}
+
+      text <a id="html-id"></a> and <a name="html-name"></a>
+
+      [backlink](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it finds no issues
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it finds no issues
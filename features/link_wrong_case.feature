@@ -0,0 +1,59 @@
+Feature: links whose target exists with different capitalization
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Two](TWO.md)
+      ![photo](Photo.PNG)
+      """
+    And file "two.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+    And file "photo.png"
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:3  link to "TWO.md" has the wrong capitalization, should be "two.md"
+      1.md:4  link to "Photo.PNG" has the wrong capitalization, should be "photo.png"
+      """
+    And all files are unchanged
+    And the exit code is 2
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:3  corrected capitalization of link to "two.md"
+      1.md:4  corrected capitalization of link to "photo.png"
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      [Two](two.md)
+      ![photo](photo.png)
+      """
+    And the exit code is 0
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      1.md:3  corrected capitalization of link to "two.md"
+      1.md:4  corrected capitalization of link to "photo.png"
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      [Two](two.md)
+      ![photo](photo.png)
+      """
+    And the exit code is 0
@@ -0,0 +1,38 @@
+Feature: percent-encoded links whose target exists with different capitalization
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      [Two](my%20Note.md)
+      """
+    And file "my note.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:3  link to "my%20Note.md" has the wrong capitalization, should be "my%20note.md"
+      """
+    And all files are unchanged
+    And the exit code is 1
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:3  corrected capitalization of link to "my%20note.md"
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      [Two](my%20note.md)
+      """
+    And the exit code is 0
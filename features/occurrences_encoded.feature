@@ -0,0 +1,40 @@
+Feature: occurrences of files with spaces in their name
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      ![logo](logo.png)
+      """
+    And file "logo.png"
+    And file "two words.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      """
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  added two words.md to occurrences section
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ![logo](logo.png)
+
+      ### occurrences
+
+      - [Two](two%20words.md)
+      """
+    And the exit code is 0
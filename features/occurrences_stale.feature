@@ -0,0 +1,63 @@
+Feature: remove stale entries from the occurrences section
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "bidiLinks": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      ![logo](logo.png)
+
+      ### occurrences
+
+      - [Two](2.md)
+      - [Three](3.md)
+      """
+    And file "logo.png"
+    And file "2.md" with content:
+      """
+      # Two
+
+      [One](1.md)
+      [Three](3.md)
+      """
+    And file "3.md" with content:
+      """
+      # Three
+
+      [Two](2.md)
+      """
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      1.md:4  missing link to 2.md
+      1.md:8  stale backlink to 3.md
+      """
+    And the exit code is 2
+
+  Scenario: fix
+    When fixing
+    Then it prints:
+      """
+      1.md:5  added 2.md to occurrences section
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ![logo](logo.png)
+
+      ### occurrences
+
+      - [Two](2.md)
+      """
+    And file "2.md" is unchanged
+    And file "3.md" is unchanged
+    And the exit code is 0
@@ -0,0 +1,60 @@
+Feature: full-text search
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # Rust
+
+      Rust has a borrow checker.
+
+      ### summary
+
+      [Go](go.md)
+      """
+    And file "go.md" with content:
+      """
+      # Go
+
+      Go has no borrow checker but borrowing rust is fun.
+
+      ### summary
+
+      rust
+      [Rust](1.md)
+      """
+
+  Scenario: words
+    When searching for "rust"
+    Then it prints:
+      """
+      1.md:1  # Rust
+      go.md:3  Go has no borrow checker but borrowing rust is fun.
+      """
+    And the exit code is 0
+
+  Scenario: phrase
+    When searching for "\"borrow checker\" fun"
+    Then it prints:
+      """
+      go.md:3  Go has no borrow checker but borrowing rust is fun.
+      """
+
+  Scenario: prefix
+    When searching for "borrow*"
+    Then it prints:
+      """
+      go.md:3  Go has no borrow checker but borrowing rust is fun.
+      1.md:3  Rust has a borrow checker.
+      """
+
+  Scenario: section scope
+    When searching for "section:summary rust"
+    Then it prints:
+      """
+      go.md:7  rust
+      """
+
+  Scenario: no results
+    When searching for "zonk"
+    Then it prints nothing
+    And the exit code is 0
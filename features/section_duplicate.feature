@@ -28,14 +28,34 @@ Feature: recognize/fix duplicate sections
 
   Scenario: fix
     When fixing
-    Then it finds no issues
-    And all files are unchanged
+    Then it prints:
+      """
+      1.md:3  merged 2 "section 1" sections
+      """
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 1
+      [Two](2.md)
+
+      content
+      """
+    And the exit code is 0
 
   Scenario: pitstop
     When doing a pitstop
     Then it prints:
       """
-      1.md:3  document contains multiple "section 1" sections
-      1.md:6  document contains multiple "section 1" sections
+      1.md:3  merged 2 "section 1" sections
       """
-    And all files are unchanged
+    And file "1.md" should contain:
+      """
+      # One
+
+      ### section 1
+      [Two](2.md)
+
+      content
+      """
+    And the exit code is 0
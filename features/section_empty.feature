@@ -32,7 +32,7 @@ Feature: recognize/fix sections without content
     Then it prints:
       """
       1.md:3  removed empty section "section 1"
-      1.md:8  removed empty section "section 3"
+      1.md:6  removed empty section "section 3"
       """
     And file "1.md" should contain:
       """
@@ -48,7 +48,7 @@ Feature: recognize/fix sections without content
     Then it prints:
       """
       1.md:3  removed empty section "section 1"
-      1.md:8  removed empty section "section 3"
+      1.md:6  removed empty section "section 3"
       """
     And file "1.md" should contain:
       """
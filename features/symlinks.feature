@@ -0,0 +1,55 @@
+Feature: follow symlinks
+
+  Background:
+    Given file "tikibase.json" with content:
+      """
+      {
+        "followSymlinks": true
+      }
+      """
+    And file "1.md" with content:
+      """
+      # One
+
+      [shared](shared.md)
+      [notes](notes/2.md)
+      """
+    And file ".shared/shared.md" with content:
+      """
+      # Shared
+
+      [One](../1.md)
+      """
+    And file ".shared/notes/2.md" with content:
+      """
+      # Two
+
+      [One](../1.md)
+      """
+    And symlink "shared.md" pointing to ".shared/shared.md"
+    And symlink "notes" pointing to ".shared/notes"
+    And symlink "dangling.md" pointing to ".shared/missing.md"
+    And symlink "loop" pointing to "."
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      dangling.md:1  symlink points to non-existing ".shared/missing.md"
+      loop:1  symlink to "." creates a cycle
+      """
+    And the exit code is 2
+
+  Scenario: fix
+    When fixing
+    Then it finds no issues
+    And all files are unchanged
+
+  Scenario: pitstop
+    When doing a pitstop
+    Then it prints:
+      """
+      dangling.md:1  symlink points to non-existing ".shared/missing.md"
+      loop:1  symlink to "." creates a cycle
+      """
+    And the exit code is 2
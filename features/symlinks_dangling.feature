@@ -0,0 +1,19 @@
+Feature: report dangling symlinks without following symlinks
+
+  Background:
+    Given file "1.md" with content:
+      """
+      # One
+
+      ![logo](logo.png)
+      """
+    And file "logo.png"
+    And symlink "dangling.md" pointing to "missing.md"
+
+  Scenario: check
+    When checking
+    Then it prints:
+      """
+      dangling.md:1  symlink points to non-existing "missing.md"
+      """
+    And the exit code is 1
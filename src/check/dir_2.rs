@@ -1,4 +1,4 @@
-use super::scanners::orphaned_resource;
+use super::scanners::{broken_symlinks, orphaned_resource};
 use super::{State2, doc_phase_2};
 use crate::database::Directory;
 use crate::fspath;
@@ -11,6 +11,8 @@ pub fn dir_phase_2(dir: &Directory, state: &mut State2) {
   for resource in dir.resources.keys() {
     orphaned_resource::scan(&fspath::join(&dir.relative_path, resource), state);
   }
+  broken_symlinks::scan(dir, state);
+  state.issues.extend(dir.read_errors.iter().cloned());
   for dir in dir.dirs.values() {
     dir_phase_2(dir, state);
   }
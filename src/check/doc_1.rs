@@ -1,22 +1,20 @@
 use super::State1;
 use super::scanners::{
-  duplicate_sections, empty_section_content, empty_section_title, footnotes, illegal_sections,
-  links, section_capitalization, section_level, unordered_sections,
+  duplicate_sections, empty_section_content, empty_section_title, encoding, footnotes,
+  illegal_sections, link_references, links, section_capitalization, section_level, stale_backlinks,
+  unordered_sections,
 };
 use crate::database::{Directory, Document};
 
 // phase 1 `Document` check
 pub fn doc_phase_1(doc: &Document, dir: &Directory, state: &mut State1) {
+  encoding::scan(doc, &mut state.issues);
   duplicate_sections::scan(doc, &mut state.issues);
   unordered_sections::scan(doc, &dir.config, &mut state.issues);
-  footnotes::scan(doc, &mut state.issues);
-  links::scan(
-    doc,
-    dir,
-    &mut state.issues,
-    &mut state.linked_resources,
-    state.base_dir,
-  );
+  footnotes::scan(doc, &dir.config, &mut state.issues);
+  link_references::scan(doc, &mut state.issues);
+  links::scan(doc, dir, &mut state.issues, state.base_dir, state.backlinks);
+  stale_backlinks::scan(doc, dir, state.base_dir, &mut state.issues);
   empty_section_title::scan(&doc.title_section, &doc.relative_path, &mut state.issues);
   for content_section in &doc.content_sections {
     empty_section_content::scan(content_section, &doc.relative_path, &mut state.issues);
@@ -5,7 +5,7 @@ use crate::database::Document;
 
 // phase 2 `Document` check
 pub fn doc_phase_2(doc: &Document, config: &Config, state: &mut State2) {
-  obsolete_occurrences::scan(doc, config, &mut state.issues);
+  obsolete_occurrences::scan(doc, config, state.backlinks, &mut state.issues);
   if config.sections.is_none() {
     for content_section in &doc.content_sections {
       section_capitalization::phase_2(
@@ -0,0 +1,149 @@
+use super::Status;
+use camino::Utf8Path;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+
+/// the results of checking external URLs, persisted between runs
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Cache {
+  entries: BTreeMap<String, Entry>,
+}
+
+/// the cached result of checking a URL
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct Entry {
+  /// when the URL was checked, in seconds since the Unix epoch
+  checked: u64,
+  #[serde(flatten)]
+  status: Status,
+}
+
+impl Cache {
+  /// loads the cache file with the given path,
+  /// keeps only results that are not older than the given number of seconds
+  pub fn load(path: &Utf8Path, now: u64, max_age: u64) -> Self {
+    // a missing or unreadable cache just means checking all URLs again
+    let Ok(text) = fs::read_to_string(path) else {
+      return Self::default();
+    };
+    let Ok(mut entries) = serde_json::from_str::<BTreeMap<String, Entry>>(&text) else {
+      return Self::default();
+    };
+    entries.retain(|_url, entry| now.saturating_sub(entry.checked) <= max_age);
+    Self { entries }
+  }
+
+  /// provides the cached result for the given URL
+  pub fn get(&self, url: &str) -> Option<&Status> {
+    self.entries.get(url).map(|entry| &entry.status)
+  }
+
+  /// stores the given result for the given URL
+  pub fn insert(&mut self, url: String, status: Status, now: u64) {
+    self.entries.insert(
+      url,
+      Entry {
+        checked: now,
+        status,
+      },
+    );
+  }
+
+  /// writes this cache into the file with the given path
+  pub fn save(&self, path: &Utf8Path) -> io::Result<()> {
+    let mut text = serde_json::to_string_pretty(&self.entries)?;
+    text.push('\n');
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(path, text)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod load {
+    use super::super::Cache;
+    use crate::check::external::Status;
+    use crate::test;
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn drops_expired_entries() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = indoc! {r#"
+        {
+          "https://old.com": { "checked": 100, "status": "ok" },
+          "https://new.com": { "checked": 900, "status": "broken", "problem": "HTTP 404" }
+        }
+        "#};
+      test::create_file("cache.json", content, dir.path());
+      let have = Cache::load(&dir.path().join("cache.json"), 1000, 500);
+      assert_eq!(have.get("https://old.com"), None);
+      assert_eq!(
+        have.get("https://new.com"),
+        Some(&Status::Broken {
+          problem: S("HTTP 404")
+        })
+      );
+    }
+
+    #[test]
+    fn missing_file() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let have = Cache::load(&dir.path().join("cache.json"), 1000, 500);
+      assert_eq!(have, Cache::default());
+    }
+
+    #[test]
+    fn invalid_file() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("cache.json", "{ zonk", dir.path());
+      let have = Cache::load(&dir.path().join("cache.json"), 1000, 500);
+      assert_eq!(have, Cache::default());
+    }
+  }
+
+  mod save {
+    use super::super::Cache;
+    use crate::check::external::Status;
+    use crate::test;
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn roundtrip() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let path = dir.path().join("cache.json");
+      let mut cache = Cache::default();
+      cache.insert(S("https://one.com"), Status::Ok, 100);
+      cache.insert(
+        S("https://two.com"),
+        Status::Redirected {
+          target: S("https://three.com/"),
+        },
+        100,
+      );
+      cache.save(&path).unwrap();
+      let want = indoc! {r#"
+        {
+          "https://one.com": {
+            "checked": 100,
+            "status": "ok"
+          },
+          "https://two.com": {
+            "checked": 100,
+            "status": "redirected",
+            "target": "https://three.com/"
+          }
+        }
+        "#};
+      pretty::assert_eq!(test::load_file("cache.json", dir.path()), want);
+      assert_eq!(Cache::load(&path, 100, 0), cache);
+    }
+  }
+}
@@ -0,0 +1,218 @@
+//! checks links and images pointing to external websites
+
+mod cache;
+mod request;
+
+use crate::check::{Issue, Location};
+use crate::database::{Directory, Tikibase};
+use crate::fspath;
+use cache::Cache;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// path of the file, relative to the Tikibase root, that stores the results of checking external links
+const CACHE_FILE: &str = ".tikibase/external-links.json";
+
+/// the result of checking an external URL
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum Status {
+  /// the URL exists
+  Ok,
+  /// the URL permanently redirects to the given target, which exists
+  Redirected { target: String },
+  /// the URL cannot be loaded
+  Broken { problem: String },
+}
+
+/// checks all external links and images in the given Tikibase,
+/// reusing results of earlier runs that are still within the configured cache duration
+#[must_use]
+pub fn scan(base: &Tikibase) -> Vec<Issue> {
+  let config = base.dir.config.external_links();
+  let mut links = vec![];
+  collect_links(&base.dir, &mut links);
+  links.retain(|(url, _location)| config.checks(url));
+  let cache_path = base.root.join(CACHE_FILE);
+  let now = now();
+  let mut cache = Cache::load(&cache_path, now, config.cache_seconds());
+  let mut unchecked: Vec<&str> = links
+    .iter()
+    .map(|(url, _location)| url.as_str())
+    .filter(|url| cache.get(url).is_none())
+    .collect();
+  unchecked.sort_unstable();
+  unchecked.dedup();
+  for (url, status) in request::check_all(&unchecked, &config) {
+    cache.insert(url, status, now);
+  }
+  let mut issues = vec![];
+  if let Err(err) = cache.save(&cache_path) {
+    issues.push(Issue::CannotWriteFile {
+      file: CACHE_FILE.into(),
+      message: err.to_string(),
+    });
+  }
+  for (url, location) in links {
+    match cache.get(&url) {
+      Some(Status::Broken { problem }) => issues.push(Issue::BrokenExternalLink {
+        location,
+        url,
+        problem: problem.clone(),
+      }),
+      Some(Status::Redirected { target }) => issues.push(Issue::RedirectedExternalLink {
+        location,
+        url,
+        target: target.clone(),
+      }),
+      Some(Status::Ok) | None => {}
+    }
+  }
+  issues
+}
+
+/// populates the given list with the URLs and locations of all external links and images in the given directory
+fn collect_links(dir: &Directory, links: &mut Vec<(String, Location)>) {
+  for doc in dir.docs.values() {
+    let references = doc
+      .links
+      .iter()
+      .map(|link| (&link.target, link.line, link.start, link.end))
+      .chain(
+        doc
+          .images
+          .iter()
+          .map(|image| (&image.src, image.line, image.start, image.end)),
+      );
+    for (url, line, start, end) in references {
+      if fspath::is_external(url) {
+        links.push((
+          url.clone(),
+          Location {
+            file: doc.relative_path.clone(),
+            line,
+            start,
+            end,
+          },
+        ));
+      }
+    }
+  }
+  for subdir in dir.dirs.values() {
+    collect_links(subdir, links);
+  }
+}
+
+/// provides the current time in seconds since the Unix epoch
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod scan {
+    use crate::check::{Issue, Location};
+    use crate::test::HttpServer;
+    use crate::{Tikibase, test};
+    use big_s::S;
+
+    #[test]
+    fn broken_and_redirected() {
+      let server = HttpServer::start(&[
+        ("/ok", "200 OK"),
+        ("/moved", "301 Moved Permanently\r\nLocation: /ok"),
+      ]);
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = format!(
+        "# One\n\n[ok]({url}/ok)\n[moved]({url}/moved)\n![missing]({url}/missing.png)\n",
+        url = server.url
+      );
+      test::create_file("1.md", &content, dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = super::super::scan(&base);
+      let want = vec![
+        Issue::RedirectedExternalLink {
+          location: Location {
+            file: S("1.md"),
+            line: 3,
+            start: 0,
+            end: 15 + server.url.len() as u32,
+          },
+          url: format!("{}/moved", server.url),
+          target: format!("{}/ok", server.url),
+        },
+        Issue::BrokenExternalLink {
+          location: Location {
+            file: S("1.md"),
+            line: 4,
+            start: 0,
+            end: 24 + server.url.len() as u32,
+          },
+          url: format!("{}/missing.png", server.url),
+          problem: S("HTTP 404"),
+        },
+      ];
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn caches_results() {
+      let server = HttpServer::start(&[("/ok", "200 OK")]);
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = format!(
+        "# One\n\n[ok]({url}/ok)\n[again]({url}/ok)\n",
+        url = server.url
+      );
+      test::create_file("1.md", &content, dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert_eq!(super::super::scan(&base), vec![]);
+      assert_eq!(server.requests(), 1);
+      assert!(dir.path().join(super::super::CACHE_FILE).exists());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert_eq!(super::super::scan(&base), vec![]);
+      assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn expired_cache() {
+      let server = HttpServer::start(&[("/ok", "200 OK")]);
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        &format!("# One\n\n[ok]({}/ok)\n", server.url),
+        dir.path(),
+      );
+      let cache = format!(
+        "{{ \"{}/ok\": {{ \"checked\": 0, \"status\": \"broken\", \"problem\": \"HTTP 500\" }} }}",
+        server.url
+      );
+      test::create_file(super::super::CACHE_FILE, &cache, dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert_eq!(super::super::scan(&base), vec![]);
+      assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn allow_and_deny() {
+      let server = HttpServer::start(&[]);
+      let dir = camino_tempfile::tempdir().unwrap();
+      let config = format!(
+        "{{ \"externalLinks\": {{ \"allow\": [\"{url}/\"], \"deny\": [\"{url}/private/\"] }} }}",
+        url = server.url
+      );
+      test::create_file("tikibase.json", &config, dir.path());
+      let content = format!(
+        "# One\n\n[private]({url}/private/one)\n[other](https://other.invalid/)\n",
+        url = server.url
+      );
+      test::create_file("1.md", &content, dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert_eq!(super::super::scan(&base), vec![]);
+      assert_eq!(server.requests(), 0);
+    }
+  }
+}
@@ -0,0 +1,283 @@
+use super::Status;
+use crate::config::ExternalLinks;
+use big_s::S;
+use core::time::Duration;
+use std::sync::Mutex;
+use std::thread;
+use ureq::{Agent, AgentBuilder, ErrorKind};
+use url::Url;
+
+/// how many redirects to follow before giving up
+const MAX_REDIRECTS: usize = 10;
+
+/// how long to wait before the first retry, later retries wait proportionally longer
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// checks the given URLs, using the configured number of parallel requests
+pub fn check_all(urls: &[&str], config: &ExternalLinks) -> Vec<(String, Status)> {
+  let agent = AgentBuilder::new()
+    .timeout(config.timeout())
+    .redirects(0)
+    .build();
+  let queue = Mutex::new(urls.iter());
+  let retries = config.retries();
+  thread::scope(|scope| {
+    let workers: Vec<_> = (0..config.concurrency().min(urls.len()))
+      .map(|_| {
+        scope.spawn(|| {
+          let mut results = vec![];
+          loop {
+            let next = queue.lock().unwrap().next();
+            let Some(url) = next else {
+              break;
+            };
+            results.push(((*url).to_owned(), check(&agent, url, retries)));
+          }
+          results
+        })
+      })
+      .collect();
+    workers
+      .into_iter()
+      .flat_map(|worker| worker.join().unwrap())
+      .collect()
+  })
+}
+
+/// checks the given URL, following redirects
+fn check(agent: &Agent, url: &str, retries: u32) -> Status {
+  if let Ok(parsed) = Url::parse(url)
+    && !matches!(parsed.scheme(), "http" | "https")
+  {
+    // only websites can be requested, URLs using other protocols count as existing
+    return Status::Ok;
+  }
+  let mut current = url.to_owned();
+  // whether the first response redirected permanently
+  let mut moved = false;
+  for redirect in 0..=MAX_REDIRECTS {
+    match request_with_retries(agent, &current, retries) {
+      Response::Success => {
+        return if moved {
+          Status::Redirected { target: current }
+        } else {
+          Status::Ok
+        };
+      }
+      Response::Redirect {
+        location,
+        permanent,
+      } => {
+        let Ok(target) = Url::parse(&current).and_then(|base| base.join(&location)) else {
+          return Status::Broken {
+            problem: format!("invalid redirect to {location}"),
+          };
+        };
+        if redirect == 0 {
+          moved = permanent;
+        }
+        current = target.into();
+      }
+      Response::Failure {
+        problem,
+        temporary: _,
+      } => return Status::Broken { problem },
+    }
+  }
+  Status::Broken {
+    problem: S("too many redirects"),
+  }
+}
+
+/// requests the given URL, retrying temporary failures the given number of times
+fn request_with_retries(agent: &Agent, url: &str, retries: u32) -> Response {
+  let mut attempt = 0;
+  loop {
+    let response = request(agent, url);
+    if attempt == retries
+      || !matches!(
+        response,
+        Response::Failure {
+          temporary: true,
+          ..
+        }
+      )
+    {
+      return response;
+    }
+    attempt += 1;
+    thread::sleep(RETRY_DELAY * attempt);
+  }
+}
+
+/// the outcome of requesting a URL once
+#[derive(Debug, Eq, PartialEq)]
+enum Response {
+  Success,
+  Redirect { location: String, permanent: bool },
+  Failure { problem: String, temporary: bool },
+}
+
+/// requests the given URL once
+fn request(agent: &Agent, url: &str) -> Response {
+  match agent.get(url).call() {
+    Ok(response) => {
+      let status = response.status();
+      if !(300..400).contains(&status) {
+        return Response::Success;
+      }
+      match response.header("location") {
+        Some(location) => Response::Redirect {
+          location: location.to_owned(),
+          permanent: matches!(status, 301 | 308),
+        },
+        None => Response::Failure {
+          problem: format!("HTTP {status} without location"),
+          temporary: false,
+        },
+      }
+    }
+    Err(ureq::Error::Status(status, _response)) => Response::Failure {
+      problem: format!("HTTP {status}"),
+      temporary: status == 429 || status >= 500,
+    },
+    Err(ureq::Error::Transport(transport)) => match transport.kind() {
+      ErrorKind::Dns => Response::Failure {
+        problem: S("domain not found"),
+        temporary: false,
+      },
+      ErrorKind::ConnectionFailed => Response::Failure {
+        problem: S("connection failed"),
+        temporary: true,
+      },
+      kind => Response::Failure {
+        problem: transport
+          .message()
+          .map_or_else(|| kind.to_string(), ToOwned::to_owned),
+        temporary: true,
+      },
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod check_all {
+    use super::super::check_all;
+    use crate::check::external::Status;
+    use crate::config::ExternalLinks;
+    use crate::test::HttpServer;
+    use big_s::S;
+
+    #[test]
+    fn statuses() {
+      let server = HttpServer::start(&[
+        ("/ok", "200 OK"),
+        ("/moved", "301 Moved Permanently\r\nLocation: /ok"),
+        ("/temporary", "302 Found\r\nLocation: /ok"),
+        ("/loop", "302 Found\r\nLocation: /loop"),
+        (
+          "/moved-to-missing",
+          "308 Permanent Redirect\r\nLocation: /missing",
+        ),
+      ]);
+      let urls = [
+        "/ok",
+        "/missing",
+        "/moved",
+        "/temporary",
+        "/loop",
+        "/moved-to-missing",
+      ]
+      .map(|path| format!("{}{path}", server.url));
+      let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+      let mut have = check_all(&urls, &ExternalLinks::default());
+      have.sort_by(|a, b| a.0.cmp(&b.0));
+      let want = vec![
+        (
+          format!("{}/loop", server.url),
+          Status::Broken {
+            problem: S("too many redirects"),
+          },
+        ),
+        (
+          format!("{}/missing", server.url),
+          Status::Broken {
+            problem: S("HTTP 404"),
+          },
+        ),
+        (
+          format!("{}/moved", server.url),
+          Status::Redirected {
+            target: format!("{}/ok", server.url),
+          },
+        ),
+        (
+          format!("{}/moved-to-missing", server.url),
+          Status::Broken {
+            problem: S("HTTP 404"),
+          },
+        ),
+        (format!("{}/ok", server.url), Status::Ok),
+        (format!("{}/temporary", server.url), Status::Ok),
+      ];
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn other_protocols() {
+      let have = check_all(&["ftp://example.com/file.zip"], &ExternalLinks::default());
+      let want = vec![(S("ftp://example.com/file.zip"), Status::Ok)];
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn retries_server_errors() {
+      let server = HttpServer::start(&[("/flaky", "503 Service Unavailable")]);
+      let url = format!("{}/flaky", server.url);
+      let config = ExternalLinks {
+        retries: Some(1),
+        ..ExternalLinks::default()
+      };
+      let have = check_all(&[&url], &config);
+      let want = vec![(
+        url.clone(),
+        Status::Broken {
+          problem: S("HTTP 503"),
+        },
+      )];
+      pretty::assert_eq!(have, want);
+      assert_eq!(server.requests(), 2);
+    }
+
+    #[test]
+    fn does_not_retry_missing_pages() {
+      let server = HttpServer::start(&[]);
+      let url = format!("{}/missing", server.url);
+      let config = ExternalLinks {
+        retries: Some(3),
+        ..ExternalLinks::default()
+      };
+      let _ = check_all(&[&url], &config);
+      assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn unreachable_server() {
+      let url = HttpServer::unused_url();
+      let config = ExternalLinks {
+        retries: Some(0),
+        ..ExternalLinks::default()
+      };
+      let have = check_all(&[&url], &config);
+      let want = vec![(
+        url.clone(),
+        Status::Broken {
+          problem: S("connection failed"),
+        },
+      )];
+      pretty::assert_eq!(have, want);
+    }
+  }
+}
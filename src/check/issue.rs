@@ -3,6 +3,11 @@ use super::Location;
 /// the issues that this linter can find
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Issue {
+  BrokenExternalLink {
+    location: Location,
+    url: String,
+    problem: String,
+  },
   BrokenImage {
     location: Location,
     target: String,
@@ -17,16 +22,27 @@ pub enum Issue {
     path: String, // TODO: make either a Utf8PathBuf or a newtype that represents a relative path
     err: String,
   },
-  // TODO: make error
+  CannotReadFile {
+    file: String,
+    message: String,
+  },
   CannotWriteConfigFile {
     message: String,
     file: String,
   },
+  CannotWriteFile {
+    file: String,
+    message: String,
+  },
   // TODO: make error
   CannotWriteJsonSchemaFile {
     file: String,
     message: String,
   },
+  DanglingSymlink {
+    location: Location,
+    target: String,
+  },
   DocumentWithoutLinks {
     location: Location,
   },
@@ -41,6 +57,12 @@ pub enum Issue {
     location: Location,
     title: String,
   },
+  ExportDirInsideTikibase {
+    path: String,
+  },
+  FileChangedSinceFix {
+    file: String,
+  },
   HeadingLevelDifferentThanConfigured {
     location: Location,
     configured_level: u8,
@@ -96,9 +118,22 @@ pub enum Issue {
   LinkToSameDocument {
     location: Location,
   },
+  LinkWithWrongCase {
+    location: Location,
+    /// the link target as written in the document
+    target: String,
+    /// the link target with the capitalization of the existing file
+    correct_target: String,
+  },
   LinkWithoutTarget {
     location: Location,
   },
+  MisnumberedFootnotes {
+    location: Location,
+  },
+  MisplacedFootnoteDefinitions {
+    location: Location,
+  },
   MissingFootnote {
     location: Location,
     identifier: String,
@@ -108,6 +143,10 @@ pub enum Issue {
     path: String,
     title: String,
   },
+  MissingLinkDefinition {
+    location: Location,
+    label: String,
+  },
   MixCapSection {
     location: Location,
     all_variants: Vec<String>,
@@ -118,6 +157,10 @@ pub enum Issue {
   NoTitleSection {
     location: Location,
   },
+  NonUtf8Document {
+    location: Location,
+  },
+  NothingToUndo,
   ObsoleteOccurrencesSection {
     location: Location,
   },
@@ -127,9 +170,26 @@ pub enum Issue {
     // during processing it.
     location: Location,
   },
+  OscillatingFix {
+    location: Location,
+  },
+  RedirectedExternalLink {
+    location: Location,
+    url: String,
+    target: String,
+  },
   SectionWithoutHeader {
     location: Location,
   },
+  StaleBacklink {
+    location: Location,
+    /// the link target of the stale entry
+    path: String,
+  },
+  SymlinkCycle {
+    location: Location,
+    target: String,
+  },
   TitleRegexNoCaptures {
     regex: String,
   },
@@ -151,8 +211,64 @@ pub enum Issue {
   UnorderedSections {
     location: Location,
   },
+  UnsettledFixes {
+    file: String,
+    passes: usize,
+  },
   UnusedFootnote {
     location: Location,
     identifier: String,
   },
+  UnusedLinkDefinition {
+    location: Location,
+    label: String,
+  },
+}
+
+impl Issue {
+  /// provides the location of this issue, if it occurs inside a file
+  pub fn location(&self) -> Option<&Location> {
+    match self {
+      Self::BrokenExternalLink { location, .. }
+      | Self::BrokenImage { location, .. }
+      | Self::CannotReadConfigurationFile { location, .. }
+      | Self::DanglingSymlink { location, .. }
+      | Self::DocumentWithoutLinks { location, .. }
+      | Self::DuplicateSection { location, .. }
+      | Self::EmptySection { location, .. }
+      | Self::HeadingLevelDifferentThanConfigured { location, .. }
+      | Self::InconsistentHeadingLevel { location, .. }
+      | Self::InvalidConfigurationFile { location, .. }
+      | Self::InvalidGlob { location, .. }
+      | Self::LinkToNonExistingAnchorInCurrentDocument { location, .. }
+      | Self::LinkToNonExistingAnchorInExistingDocument { location, .. }
+      | Self::LinkToNonExistingDir { location, .. }
+      | Self::LinkToNonExistingFile { location, .. }
+      | Self::LinkToSameDocument { location, .. }
+      | Self::LinkWithWrongCase { location, .. }
+      | Self::LinkWithoutTarget { location, .. }
+      | Self::MisnumberedFootnotes { location }
+      | Self::MisplacedFootnoteDefinitions { location }
+      | Self::MissingFootnote { location, .. }
+      | Self::MissingLink { location, .. }
+      | Self::MissingLinkDefinition { location, .. }
+      | Self::MixCapSection { location, .. }
+      | Self::NoTitleSection { location, .. }
+      | Self::NonUtf8Document { location, .. }
+      | Self::ObsoleteOccurrencesSection { location, .. }
+      | Self::OrphanedResource { location, .. }
+      | Self::OscillatingFix { location, .. }
+      | Self::RedirectedExternalLink { location, .. }
+      | Self::SectionWithoutHeader { location, .. }
+      | Self::StaleBacklink { location, .. }
+      | Self::SymlinkCycle { location, .. }
+      | Self::UnclosedBacktick { location, .. }
+      | Self::UnclosedFence { location, .. }
+      | Self::UnknownSection { location, .. }
+      | Self::UnorderedSections { location, .. }
+      | Self::UnusedFootnote { location, .. }
+      | Self::UnusedLinkDefinition { location, .. } => Some(location),
+      _ => None,
+    }
+  }
 }
@@ -2,6 +2,7 @@ mod dir_1;
 mod dir_2;
 mod doc_1;
 mod doc_2;
+pub mod external;
 mod issue;
 mod location;
 pub mod scanners;
@@ -0,0 +1,25 @@
+use crate::check::{Issue, Location, State2};
+use crate::database::{BrokenSymlink, Directory};
+use crate::fspath;
+
+/// populates the given state with issues for all symlinks in the given directory that cannot be followed
+pub fn scan(dir: &Directory, state: &mut State2) {
+  for (name, symlink) in &dir.broken_symlinks {
+    let location = Location {
+      file: fspath::join(&dir.relative_path, name),
+      line: 0,
+      start: 0,
+      end: 0,
+    };
+    state.issues.push(match symlink {
+      BrokenSymlink::Dangling { target } => Issue::DanglingSymlink {
+        location,
+        target: target.clone(),
+      },
+      BrokenSymlink::Cycle { target } => Issue::SymlinkCycle {
+        location,
+        target: target.clone(),
+      },
+    });
+  }
+}
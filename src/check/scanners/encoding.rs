@@ -0,0 +1,16 @@
+use crate::check::{Issue, Location};
+use crate::database::Document;
+
+/// populates the given issues list if the given document isn't valid UTF-8
+pub fn scan(doc: &Document, issues: &mut Vec<Issue>) {
+  if let Some(line) = doc.non_utf8_line {
+    issues.push(Issue::NonUtf8Document {
+      location: Location {
+        file: doc.relative_path.clone(),
+        line,
+        start: 0,
+        end: 0,
+      },
+    });
+  }
+}
@@ -1,8 +1,9 @@
+use crate::Config;
 use crate::check::{Issue, Location};
 use crate::database::Document;
 
 /// populates the given issues list with all sections in this document that don't match the configured sections
-pub fn scan(doc: &Document, issues: &mut Vec<Issue>) {
+pub fn scan(doc: &Document, config: &Config, issues: &mut Vec<Issue>) {
   let footnotes = match doc.footnotes() {
     Ok(footnotes) => footnotes,
     Err(issue) => {
@@ -32,15 +33,66 @@ pub fn scan(doc: &Document, issues: &mut Vec<Issue>) {
       identifier: unused_definition.identifier.clone(),
     });
   }
+  if !config.check_footnote_order() {
+    return;
+  }
+  if let Some(renumbering) = footnotes.renumbering() {
+    // the definitions get put in order after renumbering them
+    if let Some(reference) = footnotes.references.iter().find(|reference| {
+      renumbering
+        .iter()
+        .any(|(old, _new)| old == &reference.identifier)
+    }) {
+      issues.push(Issue::MisnumberedFootnotes {
+        location: Location {
+          file: doc.relative_path.clone(),
+          line: reference.line,
+          start: reference.start,
+          end: reference.end,
+        },
+      });
+      return;
+    }
+  }
+  // unused definitions get removed before the remaining ones get put in order
+  if footnotes.unused_definitions().next().is_some() {
+    return;
+  }
+  let blocks = doc.footnote_definition_blocks(&footnotes);
+  if !doc.has_footnote_definitions_at_end(&blocks, config.backlinks_section.as_deref())
+    && let Some(definition) = footnotes
+      .definitions
+      .iter()
+      .filter(|definition| definition.start == 0)
+      .min_by_key(|definition| definition.line)
+  {
+    issues.push(Issue::MisplacedFootnoteDefinitions {
+      location: Location {
+        file: doc.relative_path.clone(),
+        line: definition.line,
+        start: definition.start,
+        end: definition.end,
+      },
+    });
+  }
 }
 
 #[cfg(test)]
 mod tests {
+  use crate::Config;
   use crate::check::{Issue, Location};
   use crate::database::Document;
   use big_s::S;
   use indoc::indoc;
 
+  /// provides a configuration that checks the order of footnotes
+  fn footnote_order() -> Config {
+    Config {
+      footnote_order: Some(true),
+      ..Config::default()
+    }
+  }
+
   #[test]
   fn missing_footnote_definition() {
     let content = indoc! {"
@@ -60,7 +112,7 @@ mod tests {
                 "};
     let doc = Document::from_str("test.md", content).unwrap();
     let mut have = vec![];
-    super::scan(&doc, &mut have);
+    super::scan(&doc, &Config::default(), &mut have);
     let want = vec![Issue::MissingFootnote {
       location: Location {
         file: S("test.md"),
@@ -92,7 +144,7 @@ mod tests {
                 "};
     let doc = Document::from_str("test.md", content).unwrap();
     let mut have = vec![];
-    super::scan(&doc, &mut have);
+    super::scan(&doc, &Config::default(), &mut have);
     let want = vec![Issue::UnusedFootnote {
       location: Location {
         file: S("test.md"),
@@ -104,4 +156,93 @@ mod tests {
     }];
     pretty::assert_eq!(have, want);
   }
+
+  #[test]
+  fn misnumbered_footnotes() {
+    let content = indoc! {"
+                # Title
+                first[^2] and second[^1]
+
+                ### links
+
+                [^1]: second footnote
+                [^2]: first footnote
+                "};
+    let doc = Document::from_str("test.md", content).unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &footnote_order(), &mut have);
+    let want = vec![Issue::MisnumberedFootnotes {
+      location: Location {
+        file: S("test.md"),
+        line: 1,
+        start: 5,
+        end: 9,
+      },
+    }];
+    pretty::assert_eq!(have, want);
+  }
+
+  #[test]
+  fn footnote_order_not_configured() {
+    let content = indoc! {"
+                # Title
+                first[^2] and second[^1]
+
+                [^1]: second footnote
+                [^2]: first footnote
+
+                ### section
+
+                more text
+                "};
+    let doc = Document::from_str("test.md", content).unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &Config::default(), &mut have);
+    pretty::assert_eq!(have, vec![]);
+  }
+
+  #[test]
+  fn misplaced_footnote_definitions() {
+    let content = indoc! {"
+                # Title
+                text[^note]
+
+                [^note]: the note
+
+                ### section
+
+                more text
+                "};
+    let doc = Document::from_str("test.md", content).unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &footnote_order(), &mut have);
+    let want = vec![Issue::MisplacedFootnoteDefinitions {
+      location: Location {
+        file: S("test.md"),
+        line: 3,
+        start: 0,
+        end: 8,
+      },
+    }];
+    pretty::assert_eq!(have, want);
+  }
+
+  #[test]
+  fn ordered_footnotes() {
+    let content = indoc! {"
+                # Title
+                first[^1] and second[^note]
+
+                ### links
+
+                [^1]: first footnote
+                  continued
+
+                [^note]: second footnote
+                "};
+    let doc = Document::from_str("test.md", content).unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &footnote_order(), &mut have);
+    pretty::assert_eq!(have, vec![]);
+  }
 }
@@ -0,0 +1,99 @@
+use crate::check::{Issue, Location};
+use crate::database::Document;
+
+/// populates the given issues list with all reference-style links without definition
+/// and all link reference definitions that no link uses
+pub fn scan(doc: &Document, issues: &mut Vec<Issue>) {
+  let link_references = doc.link_references();
+  for missing_reference in link_references.missing_references() {
+    issues.push(Issue::MissingLinkDefinition {
+      location: Location {
+        file: doc.relative_path.clone(),
+        line: missing_reference.line,
+        start: missing_reference.start,
+        end: missing_reference.end,
+      },
+      label: missing_reference.label.clone(),
+    });
+  }
+  for unused_definition in link_references.unused_definitions() {
+    issues.push(Issue::UnusedLinkDefinition {
+      location: Location {
+        file: doc.relative_path.clone(),
+        line: unused_definition.line,
+        start: unused_definition.start,
+        end: unused_definition.end,
+      },
+      label: unused_definition.label.clone(),
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check::{Issue, Location};
+  use crate::database::Document;
+  use big_s::S;
+  use indoc::indoc;
+
+  #[test]
+  fn missing_and_unused() {
+    let content = indoc! {"
+      # Title
+      full [one][first], collapsed [Second][], shortcut [third]
+      missing [four][fourth] and [five][]
+      ![image][pic]
+
+      `[code][zonk]`
+
+      ### links
+
+      [first]: one.md
+      [second]: two.md \"Two\"
+      [third]: three.md
+      [pic]: image.png
+      [unused]: unused.md
+      "};
+    let doc = Document::from_str("file.md", content).unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &mut have);
+    let want = vec![
+      Issue::MissingLinkDefinition {
+        location: Location {
+          file: S("file.md"),
+          line: 2,
+          start: 8,
+          end: 22,
+        },
+        label: S("fourth"),
+      },
+      Issue::MissingLinkDefinition {
+        location: Location {
+          file: S("file.md"),
+          line: 2,
+          start: 27,
+          end: 33,
+        },
+        label: S("five"),
+      },
+      Issue::UnusedLinkDefinition {
+        location: Location {
+          file: S("file.md"),
+          line: 13,
+          start: 0,
+          end: 19,
+        },
+        label: S("unused"),
+      },
+    ];
+    pretty::assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_references() {
+    let doc = Document::from_str("file.md", "# Title\n[one](one.md) and [brackets]").unwrap();
+    let mut have = vec![];
+    super::scan(&doc, &mut have);
+    assert!(have.is_empty());
+  }
+}
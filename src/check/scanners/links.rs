@@ -1,14 +1,15 @@
 use crate::check::{Issue, Location};
-use crate::database::{Directory, Document, EntryType};
+use crate::database::{Backlinks, Directory, Document, EntryType};
 use crate::fspath;
+use core::cell::OnceCell;
 
 /// populates the given issues list with all link issues in this document
 pub fn scan(
   doc: &Document,
   dir: &Directory,
   issues: &mut Vec<Issue>,
-  linked_resources: &mut Vec<String>,
   root: &Directory,
+  backlinks: &Backlinks,
 ) {
   if dir.config.check_standalone_docs() && doc.links.is_empty() && doc.images.is_empty() {
     issues.push(Issue::DocumentWithoutLinks {
@@ -20,6 +21,14 @@ pub fn scan(
       },
     });
   }
+  let backlinks_lines = dir
+    .config
+    .backlinks_section
+    .as_ref()
+    .and_then(|title| doc.section_with_title(title))
+    .map(|section| section.line_number..=section.last_line_abs());
+  let slug_style = dir.config.slug_style();
+  let own_anchors = OnceCell::new();
   for link in &doc.links {
     if link.target.is_empty() {
       issues.push(Issue::LinkWithoutTarget {
@@ -32,16 +41,20 @@ pub fn scan(
       });
       continue;
     }
-    if link.target.starts_with("http") {
+    if fspath::is_external(&link.target) {
       // ignore external links
       continue;
     }
     let (target_file, target_anchor) = match link.target.split_once('#') {
-      Some((base, anchor)) => (base.to_owned(), format!("#{anchor}")),
-      None => (link.target.clone(), String::new()),
+      Some((base, anchor)) => (base, format!("#{anchor}")),
+      None => (link.target.as_str(), String::new()),
+    };
+    let Some(target_relative_path) =
+      fspath::resolve(&dir.relative_path, target_file, dir.config.base_path())
+    else {
+      // root-relative link to a page outside of this Tikibase
+      continue;
     };
-    let target_relative_path = fspath::join(&dir.relative_path, &target_file);
-    let target_relative_path = fspath::normalize(&target_relative_path);
     if target_relative_path == doc.relative_path {
       issues.push(Issue::LinkToSameDocument {
         location: Location {
@@ -54,7 +67,8 @@ pub fn scan(
       continue;
     }
     if link.target.starts_with('#') {
-      if !doc.has_anchor(&link.target) {
+      let own_anchors = own_anchors.get_or_init(|| doc.anchors(slug_style));
+      if !own_anchors.contains(&link.target) {
         issues.push(Issue::LinkToNonExistingAnchorInCurrentDocument {
           location: Location {
             file: doc.relative_path.clone(),
@@ -70,7 +84,7 @@ pub fn scan(
     match EntryType::from_str(&target_relative_path) {
       EntryType::Document => {
         if let Some(other_doc) = root.get_doc(&target_relative_path) {
-          if !target_anchor.is_empty() && !other_doc.has_anchor(&target_anchor) {
+          if !target_anchor.is_empty() && !other_doc.has_anchor(&target_anchor, slug_style) {
             issues.push(Issue::LinkToNonExistingAnchorInExistingDocument {
               location: Location {
                 file: doc.relative_path.clone(),
@@ -82,11 +96,19 @@ pub fn scan(
               anchor: target_anchor,
             });
           }
-          // check for backlink from doc to us
-          if dir.config.bidi_links == Some(true) {
+          // check for backlink from doc to us,
+          // entries in the backlinks section are backlinks themselves
+          let in_backlinks_section = backlinks_lines
+            .as_ref()
+            .is_some_and(|lines| lines.contains(&link.line));
+          if dir.config.bidi_links == Some(true) && !in_backlinks_section {
             let link_from_other_to_doc =
               fspath::relative(&other_doc.relative_path, &doc.relative_path);
-            if !other_doc.contains_reference_to(&link_from_other_to_doc) {
+            let other_links_to_doc = backlinks
+              .to(&doc.relative_path)
+              .iter()
+              .any(|backlink| backlink.source == other_doc.relative_path);
+            if !other_links_to_doc {
               issues.push(Issue::MissingLink {
                 location: Location {
                   file: target_relative_path,
@@ -100,29 +122,43 @@ pub fn scan(
             }
           }
         } else {
-          issues.push(Issue::LinkToNonExistingFile {
-            location: Location {
-              file: doc.relative_path.clone(),
-              line: link.line.to_owned(),
-              start: link.start.to_owned(),
-              end: link.end.to_owned(),
+          let location = Location {
+            file: doc.relative_path.clone(),
+            line: link.line.to_owned(),
+            start: link.start.to_owned(),
+            end: link.end.to_owned(),
+          };
+          issues.push(match wrong_case(root, target_file, &target_relative_path) {
+            Some(correct_file) => Issue::LinkWithWrongCase {
+              location,
+              target: link.target.clone(),
+              correct_target: format!("{correct_file}{target_anchor}"),
+            },
+            None => Issue::LinkToNonExistingFile {
+              location,
+              target: target_relative_path,
             },
-            target: target_relative_path,
           });
         };
       }
       EntryType::Resource => {
-        if root.has_resource(&target_relative_path) {
-          linked_resources.push(target_relative_path);
-        } else {
-          issues.push(Issue::LinkToNonExistingFile {
-            location: Location {
-              file: doc.relative_path.clone(),
-              line: link.line.to_owned(),
-              start: link.start.to_owned(),
-              end: link.end.to_owned(),
+        if !root.has_resource(&target_relative_path) {
+          let location = Location {
+            file: doc.relative_path.clone(),
+            line: link.line.to_owned(),
+            start: link.start.to_owned(),
+            end: link.end.to_owned(),
+          };
+          issues.push(match wrong_case(root, target_file, &target_relative_path) {
+            Some(correct_file) => Issue::LinkWithWrongCase {
+              location,
+              target: link.target.clone(),
+              correct_target: format!("{correct_file}{target_anchor}"),
+            },
+            None => Issue::LinkToNonExistingFile {
+              location,
+              target: target_relative_path,
             },
-            target: target_relative_path,
           });
         }
       }
@@ -130,14 +166,22 @@ pub fn scan(
       EntryType::Directory => {
         let target_dir = &target_relative_path[..target_relative_path.len() - 1];
         if !root.has_dir(target_dir) {
-          issues.push(Issue::LinkToNonExistingDir {
-            location: Location {
-              file: doc.relative_path.clone(),
-              line: link.line.to_owned(),
-              start: link.start.to_owned(),
-              end: link.end.to_owned(),
+          let location = Location {
+            file: doc.relative_path.clone(),
+            line: link.line.to_owned(),
+            start: link.start.to_owned(),
+            end: link.end.to_owned(),
+          };
+          issues.push(match wrong_case(root, target_file, target_dir) {
+            Some(correct_file) => Issue::LinkWithWrongCase {
+              location,
+              target: link.target.clone(),
+              correct_target: format!("{correct_file}{target_anchor}"),
+            },
+            None => Issue::LinkToNonExistingDir {
+              location,
+              target: target_dir.into(),
             },
-            target: target_dir.into(),
           });
         }
       }
@@ -145,27 +189,115 @@ pub fn scan(
   }
 
   for image in &doc.images {
-    if image.src.starts_with("http") {
+    if fspath::is_external(&image.src) {
       continue;
     }
-    let target_relative_path = fspath::join(&dir.relative_path, &image.src);
-    let target_relative_path = fspath::normalize(&target_relative_path);
-    if root.has_resource(&target_relative_path) {
-      linked_resources.push(target_relative_path);
-    } else {
-      issues.push(Issue::BrokenImage {
-        location: Location {
-          file: doc.relative_path.clone(),
-          line: image.line.to_owned(),
-          start: image.start.to_owned(),
-          end: image.end.to_owned(),
+    let Some(target_relative_path) =
+      fspath::resolve(&dir.relative_path, &image.src, dir.config.base_path())
+    else {
+      continue;
+    };
+    if !root.has_resource(&target_relative_path) {
+      let location = Location {
+        file: doc.relative_path.clone(),
+        line: image.line.to_owned(),
+        start: image.start.to_owned(),
+        end: image.end.to_owned(),
+      };
+      issues.push(match wrong_case(root, &image.src, &target_relative_path) {
+        Some(correct_src) => Issue::LinkWithWrongCase {
+          location,
+          target: image.src.clone(),
+          correct_target: correct_src,
+        },
+        None => Issue::BrokenImage {
+          location,
+          target: image.src.clone(),
         },
-        target: image.src.clone(),
       });
     }
   }
 }
 
+/// provides the given link target with the capitalization of the existing file or directory it points to,
+/// if it points to an existing one only when ignoring capitalization
+fn wrong_case(root: &Directory, target: &str, target_path: &str) -> Option<String> {
+  let actual_path = root.path_ignoring_case(target_path)?;
+  if actual_path == target_path {
+    return None;
+  }
+  Some(recase(target, &actual_path))
+}
+
+/// provides the given link target with its path segments capitalized like the given path
+fn recase(target: &str, actual_path: &str) -> String {
+  let mut actual_segments = actual_path.rsplit('/');
+  let mut segments: Vec<String> = target
+    .rsplit('/')
+    .map(|segment| {
+      if segment.is_empty() || segment == "." || segment == ".." {
+        return segment.into();
+      }
+      actual_segments
+        .next()
+        .and_then(|actual| recase_segment(segment, actual))
+        .unwrap_or_else(|| segment.into())
+    })
+    .collect();
+  segments.reverse();
+  segments.join("/")
+}
+
+/// provides the given path segment of a link target capitalized like the given actual file name,
+/// keeps percent-encoded characters encoded,
+/// provides nothing if the segment doesn't match the file name when ignoring capitalization
+fn recase_segment(segment: &str, actual: &str) -> Option<String> {
+  let mut result = String::new();
+  let mut actual_chars = actual.chars();
+  let mut rest = segment;
+  while let Some(c) = rest.chars().next() {
+    let encoded_len = encoded_prefix_len(rest);
+    if encoded_len > 0 {
+      // percent-encoded characters stay as they are
+      let (encoded, remainder) = rest.split_at(encoded_len);
+      for decoded in fspath::decode(encoded).chars() {
+        if !actual_chars
+          .next()?
+          .to_lowercase()
+          .eq(decoded.to_lowercase())
+        {
+          return None;
+        }
+      }
+      result.push_str(encoded);
+      rest = remainder;
+    } else {
+      let actual_char = actual_chars.next()?;
+      if !actual_char.to_lowercase().eq(c.to_lowercase()) {
+        return None;
+      }
+      result.push(actual_char);
+      rest = &rest[c.len_utf8()..];
+    }
+  }
+  if actual_chars.next().is_some() {
+    return None;
+  }
+  Some(result)
+}
+
+/// provides the length of the percent-encoded bytes like `%20` at the start of the given text
+fn encoded_prefix_len(text: &str) -> usize {
+  text
+    .as_bytes()
+    .chunks(3)
+    .take_while(|chunk| {
+      chunk.len() == 3 && chunk[0] == b'%' && chunk[1..].iter().all(u8::is_ascii_hexdigit)
+    })
+    .count()
+    * 3
+}
+
 #[cfg(test)]
 mod tests {
   use crate::check::{Issue, Location};
@@ -184,14 +316,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("one.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![Issue::LinkToNonExistingFile {
       location: Location {
         file: S("one.md"),
@@ -202,7 +327,6 @@ mod tests {
       target: S("non-existing.md"),
     }];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -217,14 +341,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![Issue::LinkToNonExistingAnchorInExistingDocument {
       location: Location {
         file: S("1.md"),
@@ -236,7 +353,6 @@ mod tests {
       anchor: S("#zonk"),
     }];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -246,14 +362,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![Issue::LinkToNonExistingAnchorInCurrentDocument {
       location: Location {
         file: S("1.md"),
@@ -264,7 +373,6 @@ mod tests {
       anchor: S("#zonk"),
     }];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -278,17 +386,9 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -302,14 +402,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![Issue::LinkToNonExistingFile {
       location: Location {
         file: S("1.md"),
@@ -320,7 +413,6 @@ mod tests {
       target: S("2.md"),
     }];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -339,16 +431,8 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -363,16 +447,8 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -391,14 +467,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(
       issues,
       vec![Issue::MissingLink {
@@ -412,7 +481,6 @@ mod tests {
         title: S("One"),
       }]
     );
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -423,16 +491,14 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("sub/1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
     super::scan(
       doc,
       base.dir.dirs.get("sub").unwrap(),
       &mut issues,
-      &mut linked_resources,
       &base.dir,
+      base.backlinks(),
     );
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -442,13 +508,12 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("sub/1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
     super::scan(
       doc,
       base.dir.dirs.get("sub").unwrap(),
       &mut issues,
-      &mut linked_resources,
       &base.dir,
+      base.backlinks(),
     );
     pretty::assert_eq!(
       issues,
@@ -462,7 +527,6 @@ mod tests {
         target: S("zonk.md")
       }]
     );
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -477,16 +541,8 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -500,14 +556,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(
       issues,
       vec![Issue::LinkToNonExistingDir {
@@ -520,7 +569,6 @@ mod tests {
         target: S("zonk"),
       }]
     );
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -530,14 +578,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("one.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(
       issues,
       vec![Issue::LinkWithoutTarget {
@@ -549,7 +590,6 @@ mod tests {
         }
       }]
     );
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -566,16 +606,8 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("one.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     assert!(issues.is_empty());
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -587,10 +619,9 @@ mod tests {
     let doc = base.get_doc("one/two/1.md").unwrap();
     let dir = base.get_dir("one/two").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(doc, dir, &mut issues, &mut linked_resources, &base.dir);
+    super::scan(doc, dir, &mut issues, &base.dir, base.backlinks());
     assert!(issues.is_empty());
-    assert_eq!(linked_resources, vec![S("one/two/foo.png")]);
+    assert!(!base.backlinks().to("one/two/foo.png").is_empty());
   }
 
   #[test]
@@ -601,16 +632,9 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     assert!(issues.is_empty());
-    assert_eq!(linked_resources, vec![S("foo.png")]);
+    assert!(!base.backlinks().to("foo.png").is_empty());
   }
 
   #[test]
@@ -620,14 +644,7 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     let want = vec![Issue::BrokenImage {
       location: Location {
         file: S("1.md"),
@@ -638,7 +655,6 @@ mod tests {
       target: S("zonk.png"),
     }];
     pretty::assert_eq!(issues, want);
-    assert_eq!(linked_resources, Vec::<String>::new());
   }
 
   #[test]
@@ -649,16 +665,9 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
-    super::scan(
-      doc,
-      &base.dir,
-      &mut issues,
-      &mut linked_resources,
-      &base.dir,
-    );
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, vec!["doc.pdf"]);
+    assert!(!base.backlinks().to("doc.pdf").is_empty());
   }
 
   #[test]
@@ -669,10 +678,168 @@ mod tests {
     let base = Tikibase::load(dir.path()).unwrap();
     let doc = base.get_doc("sub/1.md").unwrap();
     let mut issues = vec![];
-    let mut linked_resources = vec![];
     let subdir = base.get_dir("sub").unwrap();
-    super::scan(doc, subdir, &mut issues, &mut linked_resources, &base.dir);
+    super::scan(doc, subdir, &mut issues, &base.dir, base.backlinks());
     pretty::assert_eq!(issues, vec![]);
-    assert_eq!(linked_resources, vec!["sub/doc.pdf"]);
+    assert!(!base.backlinks().to("sub/doc.pdf").is_empty());
+  }
+
+  #[test]
+  fn links_with_wrong_case() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {"
+      # One
+
+      [two](../Two.md#section)
+      [image](../IMAGES/photo.png)
+      ![image](../images/Photo.PNG)
+      [dir](../Images/)
+      "};
+    test::create_file("sub/one.md", content, dir.path());
+    test::create_file(
+      "two.md",
+      "# Two
+
+[one](sub/one.md)
+### section
+",
+      dir.path(),
+    );
+    test::create_file("images/photo.png", "image", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let doc = base.get_doc("sub/one.md").unwrap();
+    let subdir = base.dir.get_dir("sub").unwrap();
+    let mut issues = vec![];
+    super::scan(doc, subdir, &mut issues, &base.dir, base.backlinks());
+    let want = vec![
+      Issue::LinkWithWrongCase {
+        location: Location {
+          file: S("sub/one.md"),
+          line: 2,
+          start: 0,
+          end: 24,
+        },
+        target: S("../Two.md#section"),
+        correct_target: S("../two.md#section"),
+      },
+      Issue::LinkWithWrongCase {
+        location: Location {
+          file: S("sub/one.md"),
+          line: 3,
+          start: 0,
+          end: 28,
+        },
+        target: S("../IMAGES/photo.png"),
+        correct_target: S("../images/photo.png"),
+      },
+      Issue::LinkWithWrongCase {
+        location: Location {
+          file: S("sub/one.md"),
+          line: 5,
+          start: 0,
+          end: 17,
+        },
+        target: S("../Images/"),
+        correct_target: S("../images/"),
+      },
+      Issue::LinkWithWrongCase {
+        location: Location {
+          file: S("sub/one.md"),
+          line: 4,
+          start: 0,
+          end: 29,
+        },
+        target: S("../images/Photo.PNG"),
+        correct_target: S("../images/photo.png"),
+      },
+    ];
+    pretty::assert_eq!(issues, want);
+  }
+
+  #[test]
+  fn encoded_and_titled_targets() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {r#"
+      # One
+
+      [encoded](my%20note.md#section)
+      [angle brackets](<my note.md>)
+      [title](my%20note.md "the note")
+      ![image](my%20photo.png "a photo")
+      "#};
+    test::create_file("1.md", content, dir.path());
+    test::create_file(
+      "my note.md",
+      "# My Note\n\n[one](1.md)\n\n### section\n",
+      dir.path(),
+    );
+    test::create_file("my photo.png", "image", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let doc = base.get_doc("1.md").unwrap();
+    let mut issues = vec![];
+    super::scan(doc, &base.dir, &mut issues, &base.dir, base.backlinks());
+    pretty::assert_eq!(issues, vec![]);
+    assert!(!base.backlinks().to("my photo.png").is_empty());
+  }
+
+  #[test]
+  fn root_relative_targets() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    test::create_file("tikibase.json", "{ \"basePath\": \"/wiki\" }", dir.path());
+    let content = indoc! {"
+      # One
+
+      [rust](/wiki/topics/rust.md#details)
+      [other site](/blog/post.md)
+      [missing](/wiki/topics/go.md)
+      ![logo](/wiki/logo.png)
+      "};
+    test::create_file("sub/one.md", content, dir.path());
+    test::create_file(
+      "topics/rust.md",
+      "# Rust\n\n[one](../sub/one.md)\n\n### details\n",
+      dir.path(),
+    );
+    test::create_file("logo.png", "image", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let doc = base.get_doc("sub/one.md").unwrap();
+    let subdir = base.dir.get_dir("sub").unwrap();
+    let mut issues = vec![];
+    super::scan(doc, subdir, &mut issues, &base.dir, base.backlinks());
+    let want = vec![Issue::LinkToNonExistingFile {
+      location: Location {
+        file: S("sub/one.md"),
+        line: 4,
+        start: 0,
+        end: 29,
+      },
+      target: S("topics/go.md"),
+    }];
+    pretty::assert_eq!(issues, want);
+    assert!(!base.backlinks().to("logo.png").is_empty());
+  }
+
+  mod recase {
+    use super::super::recase;
+
+    #[test]
+    fn relative_path() {
+      assert_eq!(recase("../Sub/ONE.md", "sub/One.md"), "../sub/One.md");
+    }
+
+    #[test]
+    fn directory() {
+      assert_eq!(recase("./SUB/", "sub"), "./sub/");
+    }
+
+    #[test]
+    fn percent_encoded() {
+      assert_eq!(recase("my%20Note.md", "my note.md"), "my%20note.md");
+    }
+
+    #[test]
+    fn percent_encoded_multibyte() {
+      assert_eq!(recase("%C3%A4PFEL/x.md", "äpfel/X.md"), "%C3%A4pfel/X.md");
+    }
   }
 }
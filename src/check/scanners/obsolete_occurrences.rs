@@ -1,12 +1,26 @@
+use super::stale_backlinks;
 use crate::Config;
 use crate::check::{Issue, Location};
-use crate::database::Document;
+use crate::database::{Backlinks, Document};
 
-pub fn scan(doc: &Document, config: &Config, issues: &mut Vec<Issue>) {
-  if config.bidi_links == Some(true)
-    && let Some(old_occurrences_section) = &doc.old_occurrences_section
-    && !has_missing_links_with_path(issues, &doc.relative_path)
-  {
+/// populates the given issues list with the "occurrences" section of the given document if it is obsolete,
+/// otherwise with its stale entries
+pub fn scan(doc: &Document, config: &Config, backlinks: &Backlinks, issues: &mut Vec<Issue>) {
+  if config.bidi_links != Some(true) {
+    return;
+  }
+  let Some(old_occurrences_section) = &doc.old_occurrences_section else {
+    return;
+  };
+  if has_missing_links_with_path(issues, &doc.relative_path) {
+    stale_backlinks::scan_occurrences(
+      doc,
+      old_occurrences_section,
+      config.base_path(),
+      backlinks,
+      issues,
+    );
+  } else {
     issues.push(Issue::ObsoleteOccurrencesSection {
       location: Location {
         file: doc.relative_path.clone(),
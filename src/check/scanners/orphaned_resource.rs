@@ -1,7 +1,7 @@
 use crate::check::{Issue, Location, State2};
 
 pub fn scan(relative_path: &str, state: &mut State2) {
-  if !state.linked_resources.iter().any(|l| l == relative_path) {
+  if !state.backlinks.is_referenced(relative_path) {
     state.issues.push(Issue::OrphanedResource {
       location: Location {
         file: relative_path.into(),
@@ -0,0 +1,150 @@
+use crate::check::{Issue, Location};
+use crate::database::{Backlinks, Directory, Document, Link, Section};
+use crate::fspath;
+
+/// populates the given issues list with all entries in the backlinks section of this document
+/// that point to documents which don't link to this document outside of their own backlinks section
+pub fn scan(doc: &Document, dir: &Directory, root: &Directory, issues: &mut Vec<Issue>) {
+  let Some(section_title) = &dir.config.backlinks_section else {
+    return;
+  };
+  let Some(section) = doc.section_with_title(section_title) else {
+    return;
+  };
+  let base_path = dir.config.base_path();
+  for (link, target_path) in entries(section, &dir.relative_path, base_path) {
+    let links_back = root.get_doc(&target_path).is_some_and(|other_doc| {
+      other_doc.contains_reference_outside_section(
+        &fspath::relative(&target_path, &doc.relative_path),
+        section_title,
+        base_path,
+      )
+    });
+    if !links_back {
+      issues.push(stale_backlink(doc, link));
+    }
+  }
+}
+
+/// populates the given issues list with all entries in the given "occurrences" section of the given document
+/// that point to documents which don't link to this document
+pub fn scan_occurrences(
+  doc: &Document,
+  section: &Section,
+  base_path: Option<&str>,
+  backlinks: &Backlinks,
+  issues: &mut Vec<Issue>,
+) {
+  let linking_docs = backlinks.to(&doc.relative_path);
+  for (link, target_path) in entries(section, fspath::dirname(&doc.relative_path), base_path) {
+    if !linking_docs
+      .iter()
+      .any(|backlink| backlink.source == target_path)
+    {
+      issues.push(stale_backlink(doc, link));
+    }
+  }
+}
+
+/// provides the links in the given section together with the relative paths of the files they point to
+fn entries(section: &Section, dir: &str, base_path: Option<&str>) -> Vec<(Link, String)> {
+  let mut links = vec![];
+  let mut images = vec![];
+  section.references(&mut links, &mut images);
+  links
+    .into_iter()
+    .filter_map(|link| {
+      let target_path = fspath::resolve_target(dir, &link.target, base_path)?;
+      Some((link, target_path))
+    })
+    .collect()
+}
+
+/// provides the issue for the given stale entry in the given document
+fn stale_backlink(doc: &Document, link: Link) -> Issue {
+  Issue::StaleBacklink {
+    location: Location {
+      file: doc.relative_path.clone(),
+      line: link.line,
+      start: link.start,
+      end: link.end,
+    },
+    path: link.target,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check::{Issue, Location};
+  use crate::{Tikibase, test};
+  use big_s::S;
+  use indoc::indoc;
+
+  #[test]
+  fn stale_entries() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    test::create_file(
+      "tikibase.json",
+      "{ \"bidiLinks\": true, \"backlinksSection\": \"### backlinks\" }",
+      dir.path(),
+    );
+    let content = indoc! {"
+      # One
+      text
+
+      ### backlinks
+
+      - [Two](2.md)
+      - [Three](sub/3.md)
+      - [Old](old.md)
+      "};
+    test::create_file("1.md", content, dir.path());
+    test::create_file("2.md", "# Two\n[One](1.md)", dir.path());
+    test::create_file("sub/3.md", "# Three\ntext", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let doc = base.get_doc("1.md").unwrap();
+    let mut have = vec![];
+    super::scan(doc, &base.dir, &base.dir, &mut have);
+    let want = vec![
+      Issue::StaleBacklink {
+        location: Location {
+          file: S("1.md"),
+          line: 6,
+          start: 2,
+          end: 19,
+        },
+        path: S("sub/3.md"),
+      },
+      Issue::StaleBacklink {
+        location: Location {
+          file: S("1.md"),
+          line: 7,
+          start: 2,
+          end: 15,
+        },
+        path: S("old.md"),
+      },
+    ];
+    pretty::assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_backlinks_section_configured() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {"
+      # One
+      text
+
+      ### backlinks
+
+      - [Two](2.md)
+      "};
+    test::create_file("1.md", content, dir.path());
+    test::create_file("2.md", "# Two\ntext", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let doc = base.get_doc("1.md").unwrap();
+    let mut have = vec![];
+    super::scan(doc, &base.dir, &base.dir, &mut have);
+    assert!(have.is_empty());
+  }
+}
@@ -1,11 +1,13 @@
 use super::Issue;
 use super::scanners::{section_capitalization, section_level};
+use crate::database::Backlinks;
 use ahash::AHashMap;
 
 /// state for phase 2
-pub struct State2 {
+pub struct State2<'a> {
   pub capitalization_outliers: AHashMap<String, section_capitalization::OutlierInfo>,
   pub level_outliers: AHashMap<String, section_level::OutlierInfo>,
-  pub linked_resources: Vec<String>,
+  /// the index of all references between the files in the Tikibase
+  pub backlinks: &'a Backlinks,
   pub issues: Vec<Issue>,
 }
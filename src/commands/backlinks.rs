@@ -0,0 +1,42 @@
+use crate::database::Backlink;
+use crate::{Tikibase, fspath};
+
+/// provides all references to the file with the given path
+#[must_use]
+pub fn backlinks(base: &Tikibase, target: &str) -> Vec<Backlink> {
+  base.backlinks().to(&fspath::normalize(target)).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::database::Backlink;
+  use crate::{Tikibase, test};
+  use big_s::S;
+
+  #[test]
+  fn backlinks() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    test::create_file("1.md", "# One\n[two](sub/2.md)", dir.path());
+    test::create_file("3.md", "# Three\n\n[two](./sub/2.md#section)", dir.path());
+    test::create_file("sub/2.md", "# Two\n### section\n[one](../1.md)", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let have = super::backlinks(&base, "./sub/2.md");
+    let want = vec![
+      Backlink {
+        source: S("1.md"),
+        target: S("sub/2.md"),
+        line: 1,
+        start: 0,
+        end: 15,
+      },
+      Backlink {
+        source: S("3.md"),
+        target: S("./sub/2.md#section"),
+        line: 2,
+        start: 0,
+        end: 25,
+      },
+    ];
+    pretty::assert_eq!(have, want);
+  }
+}
@@ -1,19 +1,23 @@
 use super::Outcome;
-use crate::Tikibase;
 use crate::check::scanners::{section_capitalization, section_level};
-use crate::check::{State1, State2, dir_phase_1, dir_phase_2};
+use crate::check::{Issue, State1, State2, dir_phase_1, dir_phase_2, doc_phase_1, external};
+use crate::{Tikibase, fspath};
 
+/// finds all issues in the given Tikibase, including broken external links if requested
 #[must_use]
-pub fn check(base: &Tikibase) -> Outcome {
-  let mut state_1 = State1::empty(&base.dir);
+pub fn check(base: &Tikibase, external: bool) -> Outcome {
+  let mut state_1 = State1::empty(&base.dir, base.backlinks());
   dir_phase_1(&base.dir, "", &mut state_1);
   let mut state_2 = State2 {
     capitalization_outliers: section_capitalization::find_outliers(state_1.capitalization_variants),
     level_outliers: section_level::find_outliers(state_1.level_variants),
-    linked_resources: state_1.linked_resources,
+    backlinks: state_1.backlinks,
     issues: state_1.issues,
   };
   dir_phase_2(&base.dir, &mut state_2);
+  if external {
+    state_2.issues.extend(external::scan(base));
+  }
   state_2.issues.sort();
   Outcome {
     issues: state_2.issues,
@@ -21,6 +25,28 @@ pub fn check(base: &Tikibase) -> Outcome {
   }
 }
 
+/// finds the issues in the document with the given path that the document-level checks find,
+/// without the checks that compare it to the rest of the Tikibase
+#[must_use]
+pub fn check_doc(base: &Tikibase, path: &str) -> Vec<Issue> {
+  let dir = match fspath::dirname(path) {
+    "" => Some(&base.dir),
+    dirname => base.dir.get_dir(dirname),
+  };
+  let (Some(dir), Some(doc)) = (dir, base.get_doc(path)) else {
+    return vec![];
+  };
+  let mut state = State1::empty(&base.dir, base.backlinks());
+  doc_phase_1(doc, dir, &mut state);
+  state.issues.retain(|issue| {
+    issue
+      .location()
+      .is_some_and(|location| location.file == path)
+  });
+  state.issues.sort();
+  state.issues
+}
+
 #[cfg(test)]
 mod tests {
   use crate::check::{Issue, Location};
@@ -36,7 +62,7 @@ mod tests {
     test::create_file("3.md", "# Three\n\n[one](1.md)\n", dir.path());
     test::create_file("tikibase.json", r#"{ "bidiLinks": true }"#, dir.path());
     let base = Tikibase::load(dir.path()).unwrap();
-    let have = super::check(&base);
+    let have = super::check(&base, false);
     let want = Outcome {
       issues: vec![
         Issue::DocumentWithoutLinks {
@@ -83,7 +109,7 @@ mod tests {
     );
     test::create_file("tikibase.json", r#"{ "bidiLinks": true }"#, dir.path());
     let base = Tikibase::load(dir.path()).unwrap();
-    let have = super::check(&base);
+    let have = super::check(&base, false);
     let want = Outcome {
       issues: vec![
         Issue::DocumentWithoutLinks {
@@ -0,0 +1,82 @@
+use super::Outcome;
+use crate::check::Issue;
+use crate::input::ExportFormat;
+use crate::{Tikibase, export, fspath};
+use camino::Utf8Path;
+
+/// exports the given Tikibase in the given format,
+/// relative output directories are relative to the Tikibase root
+#[must_use]
+pub fn export(base: &Tikibase, format: ExportFormat) -> Outcome {
+  match format {
+    ExportFormat::Html { outdir } => {
+      if is_loaded_by(base, &outdir) {
+        return Outcome {
+          issues: vec![Issue::ExportDirInsideTikibase { path: outdir }],
+          fixes: vec![],
+        };
+      }
+      export::html::export(base, &base.root.join(outdir))
+    }
+  }
+}
+
+/// indicates whether the given Tikibase loads the files in the given output directory,
+/// so that exported files would show up in its checks
+fn is_loaded_by(base: &Tikibase, outdir: &str) -> bool {
+  let relative = if Utf8Path::new(outdir).is_absolute() {
+    let Ok(root) = base.root.canonicalize_utf8() else {
+      return false;
+    };
+    match Utf8Path::new(outdir).strip_prefix(&root) {
+      Ok(relative) => fspath::normalize(relative.as_str()),
+      Err(_) => return false,
+    }
+  } else {
+    fspath::normalize(outdir)
+  };
+  let mut segments = relative.split('/').filter(|segment| !segment.is_empty());
+  let Some(first) = segments.next() else {
+    // the Tikibase root itself
+    return true;
+  };
+  if first == ".." || base.dir.config.ignore(first) {
+    return false;
+  }
+  // Tikibase doesn't load files and directories whose name starts with a dot
+  !first.starts_with('.') && !segments.any(|segment| segment.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod is_loaded_by {
+    use crate::{Tikibase, test};
+
+    #[test]
+    fn inside() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert!(super::super::is_loaded_by(&base, "site"));
+      assert!(super::super::is_loaded_by(&base, "./sub/site"));
+      assert!(super::super::is_loaded_by(&base, "."));
+      let absolute = dir.path().canonicalize_utf8().unwrap().join("site");
+      assert!(super::super::is_loaded_by(&base, absolute.as_str()));
+    }
+
+    #[test]
+    fn not_loaded() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n", dir.path());
+      test::create_file("tikibase.json", r#"{ "ignore": ["public"] }"#, dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      assert!(!super::super::is_loaded_by(&base, "../site"));
+      assert!(!super::super::is_loaded_by(&base, "sub/../../site"));
+      assert!(!super::super::is_loaded_by(&base, ".site"));
+      assert!(!super::super::is_loaded_by(&base, "sub/.site"));
+      assert!(!super::super::is_loaded_by(&base, "public/site"));
+      assert!(!super::super::is_loaded_by(&base, "/tmp/elsewhere"));
+    }
+  }
+}
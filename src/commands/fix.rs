@@ -1,16 +1,273 @@
 use super::Outcome;
+use crate::check::Issue;
 use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::fix::interactive::{self, Decisions, Prompt};
+use crate::input::Selection;
 use crate::{Tikibase, commands, fix};
+use ahash::{AHashMap, AHashSet, RandomState};
+use core::hash::BuildHasher;
 
-pub fn fix(base: &mut Tikibase) -> Outcome {
-  let check_result = commands::check(base);
-  let mut fix_result = Outcome::default();
-  for issue in check_result.issues {
-    match fix::fix(issue, base) {
-      Fixed(fix) => fix_result.fixes.push(fix),
-      Failed(problem) => fix_result.issues.push(problem),
-      Unfixable => {}
-    }
+/// how many passes over the Tikibase in a row may leave the number of issues unchanged before fixing stops
+pub const MAX_PASSES_WITHOUT_PROGRESS: usize = 10;
+
+pub fn fix(base: &mut Tikibase, selection: &Selection) -> Outcome {
+  base.start_journal();
+  let (mut fix_result, _unfixed) = fix_until_settled(base, selected(selection, fix::fix));
+  if let Err(issue) = base.finish_journal() {
+    fix_result.issues.push(issue);
+  }
+  fix_result
+}
+
+/// fixes the selected issues whose fixes the user approves via the given prompt
+pub fn fix_interactively<P: Prompt>(
+  base: &mut Tikibase,
+  selection: &Selection,
+  prompt: &mut P,
+) -> Outcome {
+  base.start_journal();
+  let mut decisions = Decisions::default();
+  let fixer = |issue, base: &mut Tikibase| interactive::fix(issue, base, prompt, &mut decisions);
+  let (mut fix_result, _unfixed) = fix_until_settled(base, selected(selection, fixer));
+  if let Err(issue) = base.finish_journal() {
+    fix_result.issues.push(issue);
   }
   fix_result
 }
+
+/// wraps the given fixer so that it fixes only the issues in the given selection
+pub fn selected<F: FnMut(Issue, &mut Tikibase) -> fix::Result>(
+  selection: &Selection,
+  mut fixer: F,
+) -> impl FnMut(Issue, &mut Tikibase) -> fix::Result {
+  move |issue, base| {
+    if fix::is_selected(&issue, selection) {
+      fixer(issue, base)
+    } else {
+      Unfixable
+    }
+  }
+}
+
+/// fixes all auto-fixable issues using the given fixer in passes until no more fixes apply.
+/// Each pass checks the entire Tikibase and fixes the issues of each file,
+/// re-checking only that file between its fixes so that fixes always work with up-to-date positions.
+/// Stops when several passes in a row don't reduce the number of issues.
+/// Provides the applied fixes and the problems encountered while fixing
+/// as well as the issues that remain unfixed.
+pub fn fix_until_settled<F: FnMut(Issue, &mut Tikibase) -> fix::Result>(
+  base: &mut Tikibase,
+  fixer: F,
+) -> (Outcome, Vec<Issue>) {
+  let mut fixing = Fixing {
+    fixer,
+    outcome: Outcome::default(),
+    failed: vec![],
+    stuck: AHashSet::new(),
+    changed: AHashSet::new(),
+    states: AHashMap::new(),
+    hasher: RandomState::new(),
+  };
+  let mut fewest_issues = usize::MAX;
+  let mut passes_without_progress = 0;
+  while passes_without_progress < MAX_PASSES_WITHOUT_PROGRESS {
+    let issues = commands::check(base, false).issues;
+    if issues.len() < fewest_issues {
+      fewest_issues = issues.len();
+      passes_without_progress = 0;
+    } else {
+      passes_without_progress += 1;
+    }
+    fixing.changed.clear();
+    let mut unfixed = vec![];
+    for issue in issues {
+      if let Some(location) = issue.location() {
+        if fixing.changed.contains(&location.file) {
+          continue;
+        }
+        if fixing.stuck.contains(&location.file) {
+          unfixed.push(issue);
+          continue;
+        }
+      }
+      if fixing.failed.contains(&issue) {
+        continue;
+      }
+      match fixing.apply(issue.clone(), base) {
+        Some(file) => fixing.settle(&file, base),
+        None => unfixed.push(issue),
+      }
+    }
+    if fixing.changed.is_empty() {
+      return (fixing.outcome, unfixed);
+    }
+  }
+  let mut unsettled: Vec<String> = fixing.changed.into_iter().collect();
+  unsettled.sort();
+  for file in unsettled {
+    fixing.outcome.issues.push(Issue::UnsettledFixes {
+      file,
+      passes: MAX_PASSES_WITHOUT_PROGRESS,
+    });
+  }
+  (fixing.outcome, commands::check(base, false).issues)
+}
+
+/// the state of fixing a Tikibase
+struct Fixing<F: FnMut(Issue, &mut Tikibase) -> fix::Result> {
+  fixer: F,
+  /// the applied fixes and the problems encountered while fixing
+  outcome: Outcome,
+  /// issues whose fix failed, these don't get fixed again
+  failed: Vec<Issue>,
+  /// files in which fixes undo each other, these don't get fixed anymore
+  stuck: AHashSet<String>,
+  /// files changed in the current pass
+  changed: AHashSet<String>,
+  /// file --> hashes of the states that fixing has produced in its document so far, including the original one
+  states: AHashMap<String, AHashSet<u64>>,
+  hasher: RandomState,
+}
+
+impl<F: FnMut(Issue, &mut Tikibase) -> fix::Result> Fixing<F> {
+  /// fixes the given issue, provides the file that the fix changed
+  fn apply(&mut self, issue: Issue, base: &mut Tikibase) -> Option<String> {
+    if let Some(location) = issue.location()
+      && !self.states.contains_key(&location.file)
+    {
+      let original = self.state(&location.file, base);
+      self
+        .states
+        .insert(location.file.clone(), [original].into_iter().collect());
+    }
+    match (self.fixer)(issue.clone(), base) {
+      Fixed(fix) => {
+        let file = String::from(fix.file());
+        let state = self.state(&file, base);
+        // a fix that returns the document to a state it had before undoes an earlier fix
+        if !self.states.entry(file.clone()).or_default().insert(state) {
+          self.outcome.issues.push(Issue::OscillatingFix {
+            location: issue.location().cloned().unwrap_or_default(),
+          });
+          self.stuck.insert(file.clone());
+        }
+        self.outcome.fixes.push(fix);
+        self.changed.insert(file.clone());
+        Some(file)
+      }
+      Failed(problem) => {
+        self.outcome.issues.push(problem);
+        self.failed.push(issue);
+        None
+      }
+      Unfixable => None,
+    }
+  }
+
+  /// provides a hash of the current state of the document with the given path
+  fn state(&self, file: &str, base: &Tikibase) -> u64 {
+    self.hasher.hash_one(base.get_doc(file))
+  }
+
+  /// fixes the remaining issues of the given file one at a time, re-checking only this file after each fix,
+  /// as long as the fixes reduce the number of issues in it
+  fn settle(&mut self, file: &str, base: &mut Tikibase) {
+    let mut unfixable: Vec<Issue> = vec![];
+    let mut issue_count = usize::MAX;
+    while !self.stuck.contains(file) {
+      let issues = commands::check_doc(base, file);
+      if issues.len() >= issue_count {
+        return;
+      }
+      issue_count = issues.len();
+      let mut fixed = false;
+      for issue in issues {
+        if self.failed.contains(&issue) || unfixable.contains(&issue) {
+          continue;
+        }
+        if self.apply(issue.clone(), base).is_some() {
+          fixed = true;
+          break;
+        }
+        unfixable.push(issue);
+      }
+      if !fixed {
+        return;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod fix_until_settled {
+    use crate::check::{Issue, Location};
+    use crate::fix::Fix::CorrectedLinkCase;
+    use crate::fix::Result::{Fixed, Unfixable};
+    use crate::{Tikibase, fix, test};
+    use big_s::S;
+
+    #[test]
+    fn identical_fixes() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = "# One\n\n[Two](two.md)\n\n[^a]: unused\n[^a]: unused\n";
+      test::create_file("1.md", content, dir.path());
+      test::create_file("two.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let (outcome, unfixed) = super::super::fix_until_settled(&mut base, fix::fix);
+      pretty::assert_eq!(outcome.issues, vec![]);
+      assert_eq!(outcome.fixes.len(), 2);
+      assert!(unfixed.is_empty());
+      let want = "# One\n\n[Two](two.md)\n";
+      assert_eq!(test::load_file("1.md", dir.path()), want);
+    }
+
+    #[test]
+    fn many_fixes_in_one_document() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let links: Vec<String> = (0..120).map(|_| S("[Two](TWO.md)")).collect();
+      let content = format!("# One\n\n{}\n", links.join("\n"));
+      test::create_file("1.md", &content, dir.path());
+      test::create_file("two.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let (outcome, unfixed) = super::super::fix_until_settled(&mut base, fix::fix);
+      assert_eq!(outcome.fixes.len(), 120);
+      assert!(outcome.issues.is_empty());
+      assert!(unfixed.is_empty());
+      assert!(!test::load_file("1.md", dir.path()).contains("TWO.md"));
+    }
+
+    #[test]
+    fn repeated_fix() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\n[Two](TWO.md)\n", dir.path());
+      test::create_file("two.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      // a fixer that claims to fix the issue without changing anything
+      let fixer = |issue: Issue, _base: &mut Tikibase| match issue {
+        Issue::LinkWithWrongCase {
+          location,
+          target,
+          correct_target,
+        } => Fixed(CorrectedLinkCase {
+          location,
+          target,
+          correct_target,
+        }),
+        _ => Unfixable,
+      };
+      let (outcome, _unfixed) = super::super::fix_until_settled(&mut base, fixer);
+      assert_eq!(outcome.fixes.len(), 1);
+      let want = vec![Issue::OscillatingFix {
+        location: Location {
+          file: S("1.md"),
+          line: 2,
+          start: 0,
+          end: 13,
+        },
+      }];
+      pretty::assert_eq!(outcome.issues, want);
+    }
+  }
+}
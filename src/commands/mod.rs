@@ -1,20 +1,28 @@
 //! High-level commands that the Tikibase linter can execute.
 
+mod backlinks;
 mod check;
+mod export;
 mod fix;
 mod init;
 mod json_schema;
 mod pitstop;
+mod search;
 mod stats;
+mod undo;
 
 use crate::Fix;
 use crate::check::Issue;
-pub use check::check;
-pub use fix::fix;
+pub use backlinks::backlinks;
+pub use check::{check, check_doc};
+pub use export::export;
+pub use fix::{fix, fix_interactively};
 pub use init::init;
 pub use json_schema::json_schema;
 pub use pitstop::pitstop;
+pub use search::search;
 pub use stats::stats;
+pub use undo::undo;
 
 /// The inner API of the check subsystem.
 /// This data structure is returned by the probes.
@@ -1,17 +1,18 @@
 use super::Outcome;
-use crate::fix::Result::{Failed, Fixed, Unfixable};
-use crate::fix::fix;
-use crate::{Tikibase, commands};
+use super::fix::{fix_until_settled, selected};
+use crate::input::Selection;
+use crate::{Tikibase, fix};
 
-pub fn pitstop(base: &mut Tikibase) -> Outcome {
-  let check_result = commands::check(base);
-  let mut pitstop_result = Outcome::default();
-  for issue in check_result.issues {
-    match fix(issue.clone(), base) {
-      Fixed(fix) => pitstop_result.fixes.push(fix),
-      Failed(problem) => pitstop_result.issues.push(problem),
-      Unfixable => pitstop_result.issues.push(issue),
-    }
+pub fn pitstop(base: &mut Tikibase, selection: &Selection) -> Outcome {
+  base.start_journal();
+  let (fix_result, mut issues) = fix_until_settled(base, selected(selection, fix::fix));
+  issues.extend(fix_result.issues);
+  let mut pitstop_result = Outcome {
+    issues,
+    fixes: fix_result.fixes,
+  };
+  if let Err(issue) = base.finish_journal() {
+    pitstop_result.issues.push(issue);
   }
   pitstop_result
 }
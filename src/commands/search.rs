@@ -0,0 +1,30 @@
+use crate::Tikibase;
+use crate::database::{Hit, Query};
+
+/// provides the documents matching the given search query, most relevant first
+#[must_use]
+pub fn search(base: &Tikibase, query: &str) -> Vec<Hit> {
+  base.search_index().search(&Query::parse(query))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{Tikibase, test};
+
+  #[test]
+  fn search() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    test::create_file("1.md", "# One\nthe borrow checker", dir.path());
+    test::create_file("sub/2.md", "# Borrowing\ntext", dir.path());
+    let base = Tikibase::load(dir.path()).unwrap();
+    let have: Vec<(String, u32, String)> = super::search(&base, "borrow*")
+      .into_iter()
+      .map(|hit| (hit.file, hit.line, hit.text))
+      .collect();
+    let want = vec![
+      ("sub/2.md".into(), 0, "# Borrowing".into()),
+      ("1.md".into(), 1, "the borrow checker".into()),
+    ];
+    pretty::assert_eq!(have, want);
+  }
+}
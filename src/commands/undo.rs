@@ -0,0 +1,21 @@
+use super::Outcome;
+use crate::{Fix, database};
+use camino::Utf8Path;
+
+/// restores the files changed by the last fix
+#[must_use]
+pub fn undo(root: &Utf8Path) -> Outcome {
+  match database::undo(root) {
+    Ok(files) => Outcome {
+      issues: vec![],
+      fixes: files
+        .into_iter()
+        .map(|file| Fix::RestoredFile { file })
+        .collect(),
+    },
+    Err(issues) => Outcome {
+      issues,
+      fixes: vec![],
+    },
+  }
+}
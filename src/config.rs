@@ -2,6 +2,7 @@ use crate::check::{Issue, Location};
 use crate::database::Section;
 use big_s::S;
 use camino::Utf8Path;
+use core::time::Duration;
 use fs_err::File;
 use merge::Merge;
 use regex::Regex;
@@ -17,12 +18,36 @@ pub struct Config {
   /// enables bi-directional links
   pub bidi_links: Option<bool>,
 
+  /// format of the entries in the generated backlinks section,
+  /// "{title}" and "{path}" get replaced with the title and path of the linking document
+  pub backlinks_format: Option<String>,
+
+  /// title of a fully generated section that lists all documents linking to the document, e.g. "### backlinks"
+  pub backlinks_section: Option<String>,
+
+  /// URL path under which this Tikibase is published as a website, e.g. "/wiki",
+  /// root-relative links starting with it point into the Tikibase
+  pub base_path: Option<String>,
+
+  /// how to check external links when running with "--external"
+  pub external_links: Option<ExternalLinks>,
+
+  /// whether to load the files and directories that symlinks point to
+  pub follow_symlinks: Option<bool>,
+
+  /// whether numeric footnotes must be numbered in the order of their first reference
+  /// and all footnote definitions must be at the end of the document
+  pub footnote_order: Option<bool>,
+
   /// Names of filesystem entries to ignore in this directory.
   pub ignore: Option<Vec<String>>,
 
   /// the allowed section titles
   pub sections: Option<Vec<String>>,
 
+  /// how the Markdown renderer that displays this Tikibase creates anchors for headings
+  pub slug_style: Option<SlugStyle>,
+
   /// regex with a single capture group to extract a shorter title for links to notes
   pub title_reg_ex: Option<String>,
 
@@ -35,6 +60,21 @@ pub struct Config {
 }
 
 impl Config {
+  /// provides the format of entries in the generated backlinks section
+  #[must_use]
+  pub fn backlinks_format(&self) -> &str {
+    match &self.backlinks_format {
+      Some(format) => format,
+      None => "- [{title}]({path})",
+    }
+  }
+
+  /// provides the configuration for checking external links
+  #[must_use]
+  pub fn external_links(&self) -> ExternalLinks {
+    self.external_links.clone().unwrap_or_default()
+  }
+
   /// indicates whether the given file should be ignored
   #[must_use]
   pub fn ignore(&self, file_path: &str) -> bool {
@@ -71,6 +111,30 @@ impl Config {
     None
   }
 
+  /// indicates whether to load the files and directories that symlinks point to
+  #[must_use]
+  pub fn follow_symlinks(&self) -> bool {
+    self.follow_symlinks.unwrap_or_default()
+  }
+
+  /// indicates whether to check the numbering and placement of footnotes
+  #[must_use]
+  pub fn check_footnote_order(&self) -> bool {
+    self.footnote_order.unwrap_or_default()
+  }
+
+  /// provides the URL path under which this Tikibase is published
+  #[must_use]
+  pub fn base_path(&self) -> Option<&str> {
+    self.base_path.as_deref()
+  }
+
+  /// provides how to create anchors for headings
+  #[must_use]
+  pub fn slug_style(&self) -> SlugStyle {
+    self.slug_style.unwrap_or_default()
+  }
+
   /// indicates whether Tikibase should check for standalone documents
   #[must_use]
   pub fn check_standalone_docs(&self) -> bool {
@@ -96,6 +160,85 @@ impl Config {
   }
 }
 
+/// configuration for checking external links
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalLinks {
+  /// if provided, checks only URLs starting with one of these prefixes
+  pub allow: Option<Vec<String>>,
+
+  /// never checks URLs starting with one of these prefixes
+  pub deny: Option<Vec<String>>,
+
+  /// for how many hours to reuse the result of checking a URL, default 24
+  pub cache_hours: Option<u64>,
+
+  /// how many URLs to check at the same time, default 8
+  pub concurrency: Option<usize>,
+
+  /// how often to retry a URL that fails temporarily, default 2
+  pub retries: Option<u32>,
+
+  /// how many seconds to wait for a server to respond, default 10
+  pub timeout_seconds: Option<u64>,
+}
+
+impl ExternalLinks {
+  /// indicates whether the given URL should be checked
+  #[must_use]
+  pub fn checks(&self, url: &str) -> bool {
+    let allowed = match &self.allow {
+      Some(prefixes) => prefixes.iter().any(|prefix| url.starts_with(prefix)),
+      None => true,
+    };
+    let denied = match &self.deny {
+      Some(prefixes) => prefixes.iter().any(|prefix| url.starts_with(prefix)),
+      None => false,
+    };
+    allowed && !denied
+  }
+
+  /// provides for how many seconds cached results remain valid
+  #[must_use]
+  pub fn cache_seconds(&self) -> u64 {
+    self.cache_hours.unwrap_or(24) * 60 * 60
+  }
+
+  /// provides how many URLs to check at the same time
+  #[must_use]
+  pub fn concurrency(&self) -> usize {
+    self.concurrency.unwrap_or(8).max(1)
+  }
+
+  /// provides how often to retry a URL that fails temporarily
+  #[must_use]
+  pub fn retries(&self) -> u32 {
+    self.retries.unwrap_or(2)
+  }
+
+  /// provides how long to wait for a server to respond
+  #[must_use]
+  pub fn timeout(&self) -> Duration {
+    Duration::from_secs(self.timeout_seconds.unwrap_or(10))
+  }
+}
+
+/// the algorithms that Markdown renderers use to create anchors for headings
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugStyle {
+  /// kebab-case, the algorithm Tikibase has always used
+  #[default]
+  Kebab,
+  /// GitHub
+  Github,
+  /// GitLab
+  Gitlab,
+  /// Pandoc
+  Pandoc,
+}
+
 /// reads the config file
 pub fn load(dir: &Utf8Path) -> LoadResult {
   let config_path = dir.join("tikibase.json");
@@ -140,6 +283,26 @@ pub enum LoadResult {
 #[cfg(test)]
 mod tests {
 
+  mod backlinks_format {
+    use crate::Config;
+    use big_s::S;
+
+    #[test]
+    fn configured() {
+      let config = Config {
+        backlinks_format: Some(S("- {title}: {path}")),
+        ..Config::default()
+      };
+      assert_eq!(config.backlinks_format(), "- {title}: {path}");
+    }
+
+    #[test]
+    fn default() {
+      let config = Config::default();
+      assert_eq!(config.backlinks_format(), "- [{title}]({path})");
+    }
+  }
+
   mod check_standalone_docs {
     use crate::Config;
 
@@ -171,6 +334,38 @@ mod tests {
     }
   }
 
+  mod external_links {
+    use crate::config::ExternalLinks;
+    use big_s::S;
+
+    #[test]
+    fn checks_everything_by_default() {
+      let config = ExternalLinks::default();
+      assert!(config.checks("https://example.com"));
+    }
+
+    #[test]
+    fn allow() {
+      let config = ExternalLinks {
+        allow: Some(vec![S("https://example.com/")]),
+        ..ExternalLinks::default()
+      };
+      assert!(config.checks("https://example.com/one"));
+      assert!(!config.checks("https://other.com/one"));
+    }
+
+    #[test]
+    fn deny() {
+      let config = ExternalLinks {
+        allow: Some(vec![S("https://example.com/")]),
+        deny: Some(vec![S("https://example.com/private")]),
+        ..ExternalLinks::default()
+      };
+      assert!(config.checks("https://example.com/one"));
+      assert!(!config.checks("https://example.com/private/one"));
+    }
+  }
+
   mod ignore {
     use crate::Config;
     use big_s::S;
@@ -225,7 +420,14 @@ mod tests {
       let have = load(dir.path());
       let want = LoadResult::Loaded(Config {
         bidi_links: None,
+        backlinks_format: None,
+        backlinks_section: None,
+        base_path: None,
+        external_links: None,
+        follow_symlinks: None,
+        footnote_order: None,
         sections: None,
+        slug_style: None,
         ignore: None,
         schema: None,
         title_reg_ex: None,
@@ -248,7 +450,14 @@ mod tests {
       let have = load(dir.path());
       let want = LoadResult::Loaded(Config {
         bidi_links: Some(true),
+        backlinks_format: None,
+        backlinks_section: None,
+        base_path: None,
+        external_links: None,
+        follow_symlinks: None,
+        footnote_order: None,
         sections: Some(vec![S("one"), S("two")]),
+        slug_style: None,
         ignore: Some(vec![S("foo")]),
         schema: None,
         title_reg_ex: None,
@@ -269,7 +478,7 @@ mod tests {
       let have = load(dir.path());
       let want = LoadResult::Error(Issue::InvalidConfigurationFile {
         message: S(
-          "unknown field `foo`, expected one of `bidiLinks`, `ignore`, `sections`, `titleRegEx`, `$schema`, `standaloneDocs` at line 3 column 20",
+          "unknown field `foo`, expected one of `bidiLinks`, `backlinksFormat`, `backlinksSection`, `basePath`, `externalLinks`, `followSymlinks`, `footnoteOrder`, `ignore`, `sections`, `slugStyle`, `titleRegEx`, `$schema`, `standaloneDocs` at line 3 column 20",
         ),
         location: Location {
           file: S("tikibase.json"),
@@ -337,6 +546,7 @@ mod tests {
 
   mod merge {
     use crate::Config;
+    use crate::config::{ExternalLinks, SlugStyle};
     use big_s::S;
     use merge::Merge;
 
@@ -344,8 +554,18 @@ mod tests {
     fn merge_default() {
       let mut config1 = Config {
         bidi_links: Some(true),
+        backlinks_format: Some(S("- {title}: {path}")),
+        backlinks_section: Some(S("### backlinks")),
+        base_path: None,
+        external_links: Some(ExternalLinks {
+          allow: Some(vec![S("https://")]),
+          ..ExternalLinks::default()
+        }),
+        follow_symlinks: None,
+        footnote_order: None,
         ignore: Some(vec![S("one"), S("two")]),
         sections: Some(vec![S("hello"), S("bye")]),
+        slug_style: Some(SlugStyle::Github),
         title_reg_ex: Some(S("config2regex")),
         schema: Some(S("config2schema")),
         standalone_docs: Some(true),
@@ -361,8 +581,18 @@ mod tests {
       let mut config1 = Config::default();
       let config2 = Config {
         bidi_links: Some(true),
+        backlinks_format: Some(S("- {title}: {path}")),
+        backlinks_section: Some(S("### backlinks")),
+        base_path: None,
+        external_links: Some(ExternalLinks {
+          allow: Some(vec![S("https://")]),
+          ..ExternalLinks::default()
+        }),
+        follow_symlinks: None,
+        footnote_order: None,
         ignore: Some(vec![S("one"), S("two")]),
         sections: Some(vec![S("hello"), S("bye")]),
+        slug_style: Some(SlugStyle::Github),
         title_reg_ex: Some(S("config2regex")),
         schema: Some(S("config2schema")),
         standalone_docs: Some(true),
@@ -375,16 +605,36 @@ mod tests {
     fn both_have_values() {
       let mut config1 = Config {
         bidi_links: Some(true),
+        backlinks_format: Some(S("- {title}: {path}")),
+        backlinks_section: Some(S("### backlinks")),
+        base_path: None,
+        external_links: Some(ExternalLinks {
+          allow: Some(vec![S("https://")]),
+          ..ExternalLinks::default()
+        }),
+        follow_symlinks: None,
+        footnote_order: None,
         ignore: Some(vec![S("one"), S("two")]),
         sections: Some(vec![S("hello"), S("bye")]),
+        slug_style: Some(SlugStyle::Github),
         title_reg_ex: Some(S("config2regex")),
         schema: Some(S("config2schema")),
         standalone_docs: Some(true),
       };
       let config2 = Config {
         bidi_links: Some(true),
+        backlinks_format: Some(S("- {title}: {path}")),
+        backlinks_section: Some(S("### backlinks")),
+        base_path: None,
+        external_links: Some(ExternalLinks {
+          allow: Some(vec![S("https://")]),
+          ..ExternalLinks::default()
+        }),
+        follow_symlinks: None,
+        footnote_order: None,
         ignore: Some(vec![S("one"), S("two")]),
         sections: Some(vec![S("hello"), S("bye")]),
+        slug_style: Some(SlugStyle::Github),
         title_reg_ex: Some(S("config2regex")),
         schema: Some(S("config2schema")),
         standalone_docs: Some(true),
@@ -0,0 +1,293 @@
+use super::{Directory, Document};
+use crate::fspath;
+use ahash::{AHashMap, AHashSet};
+
+/// reverse index of all links and images in a Tikibase:
+/// for each file, which documents reference it
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Backlinks {
+  /// relative path of the referenced file --> references to it
+  targets: AHashMap<String, Vec<Backlink>>,
+  /// lowercased relative paths of the referenced files that don't exist with this exact capitalization
+  missing_targets_lowercase: AHashSet<String>,
+}
+
+impl Backlinks {
+  /// provides the backlinks index for the given directory and all its subdirectories
+  pub fn from_dir(dir: &Directory) -> Self {
+    let mut result = Self::default();
+    result.add_dir(dir);
+    for references in result.targets.values_mut() {
+      references.sort();
+    }
+    result.index_missing_targets(dir);
+    result
+  }
+
+  /// replaces the references of the document with the given relative path
+  /// with the ones it currently contains, the given directory is the Tikibase root
+  pub fn update_doc(&mut self, root: &Directory, path: &str) {
+    for references in self.targets.values_mut() {
+      references.retain(|backlink| backlink.source != path);
+    }
+    self
+      .targets
+      .retain(|_target, references| !references.is_empty());
+    let dir = match fspath::dirname(path) {
+      "" => Some(root),
+      dirname => root.get_dir(dirname),
+    };
+    if let (Some(dir), Some(doc)) = (dir, root.get_doc(path)) {
+      self.add_doc(dir, doc);
+      for references in self.targets.values_mut() {
+        if references.iter().any(|backlink| backlink.source == path) {
+          references.sort();
+        }
+      }
+    }
+    self.index_missing_targets(root);
+  }
+
+  /// provides all references to the file with the given relative path,
+  /// ordered by source document and position
+  pub fn to(&self, path: &str) -> &[Backlink] {
+    match self.targets.get(path) {
+      Some(references) => references,
+      None => &[],
+    }
+  }
+
+  /// indicates whether any document references the existing file with the given relative path,
+  /// including references with the wrong capitalization that don't point to another existing file
+  pub fn is_referenced(&self, path: &str) -> bool {
+    self.targets.contains_key(path)
+      || self
+        .missing_targets_lowercase
+        .contains(&path.to_lowercase())
+  }
+
+  /// provides the relative paths of all files that documents reference
+  pub fn targets(&self) -> impl Iterator<Item = &str> {
+    self.targets.keys().map(String::as_str)
+  }
+
+  fn add_dir(&mut self, dir: &Directory) {
+    for doc in dir.docs.values() {
+      self.add_doc(dir, doc);
+    }
+    for subdir in dir.dirs.values() {
+      self.add_dir(subdir);
+    }
+  }
+
+  /// registers the references in the given document, located in the given directory
+  fn add_doc(&mut self, dir: &Directory, doc: &Document) {
+    for link in &doc.links {
+      self.add(
+        dir,
+        Backlink {
+          source: doc.relative_path.clone(),
+          target: link.target.clone(),
+          line: link.line,
+          start: link.start,
+          end: link.end,
+        },
+      );
+    }
+    for image in &doc.images {
+      self.add(
+        dir,
+        Backlink {
+          source: doc.relative_path.clone(),
+          target: image.src.clone(),
+          line: image.line,
+          start: image.start,
+          end: image.end,
+        },
+      );
+    }
+  }
+
+  /// determines the referenced files that don't exist with the exact capitalization of the reference
+  fn index_missing_targets(&mut self, root: &Directory) {
+    self.missing_targets_lowercase = self
+      .targets
+      .keys()
+      .filter(|target| !root.has_resource(target) && root.get_doc(target).is_none())
+      .map(|target| target.to_lowercase())
+      .collect();
+  }
+
+  /// registers the given reference, located in a document in the given directory
+  fn add(&mut self, dir: &Directory, backlink: Backlink) {
+    let Some(target_path) =
+      fspath::resolve_target(&dir.relative_path, &backlink.target, dir.config.base_path())
+    else {
+      return;
+    };
+    if target_path == backlink.source {
+      return;
+    }
+    self.targets.entry(target_path).or_default().push(backlink);
+  }
+}
+
+/// a link or image in a document that points to another file in the Tikibase
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Backlink {
+  /// relative path of the document containing the reference
+  pub source: String,
+  /// the reference target as written in the source document
+  pub target: String,
+  /// the line on which the reference exists
+  pub line: u32,
+  /// where on the line the reference starts
+  pub start: u32,
+  /// where on the line the reference ends
+  pub end: u32,
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod from_dir {
+    use crate::database::{Backlink, Backlinks, Tikibase};
+    use crate::test;
+    use big_s::S;
+
+    #[test]
+    fn links_and_images() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        "# One\n[two](sub/2.md)\n![image](img.png)",
+        dir.path(),
+      );
+      test::create_file("sub/2.md", "# Two\n\n[one](../1.md#section)", dir.path());
+      test::create_file("img.png", "image", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = Backlinks::from_dir(&base.dir);
+      pretty::assert_eq!(
+        have.to("1.md"),
+        vec![Backlink {
+          source: S("sub/2.md"),
+          target: S("../1.md#section"),
+          line: 2,
+          start: 0,
+          end: 22,
+        }]
+      );
+      pretty::assert_eq!(
+        have.to("sub/2.md"),
+        vec![Backlink {
+          source: S("1.md"),
+          target: S("sub/2.md"),
+          line: 1,
+          start: 0,
+          end: 15,
+        }]
+      );
+      pretty::assert_eq!(
+        have.to("img.png"),
+        vec![Backlink {
+          source: S("1.md"),
+          target: S("img.png"),
+          line: 2,
+          start: 0,
+          end: 17,
+        }]
+      );
+    }
+
+    #[test]
+    fn ignores_external_and_internal_links() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        "# One\n[google](https://google.com)\n[section](#section)\n[self](1.md)\n### section\n",
+        dir.path(),
+      );
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = Backlinks::from_dir(&base.dir);
+      assert_eq!(have, Backlinks::default());
+    }
+  }
+  mod is_referenced {
+    use crate::database::{Backlinks, Tikibase};
+    use crate::test;
+
+    #[test]
+    fn exact_case() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        "# One
+![logo](logo.png)
+",
+        dir.path(),
+      );
+      test::create_file("logo.png", "", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let backlinks = Backlinks::from_dir(&base.dir);
+      assert!(backlinks.is_referenced("logo.png"));
+      assert!(!backlinks.is_referenced("other.png"));
+    }
+
+    #[test]
+    fn wrong_case() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        "# One
+![logo](LOGO.png)
+",
+        dir.path(),
+      );
+      test::create_file("logo.png", "", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let backlinks = Backlinks::from_dir(&base.dir);
+      assert!(backlinks.is_referenced("logo.png"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn other_file_with_different_case() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "1.md",
+        "# One
+![logo](logo.png)
+",
+        dir.path(),
+      );
+      test::create_file("logo.png", "", dir.path());
+      test::create_file("Logo.png", "", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let backlinks = Backlinks::from_dir(&base.dir);
+      assert!(backlinks.is_referenced("logo.png"));
+      assert!(!backlinks.is_referenced("Logo.png"));
+    }
+  }
+
+  mod update_doc {
+    use crate::database::{Backlinks, Tikibase};
+    use crate::test;
+
+    #[test]
+    fn changed_references() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\n[two](sub/2.md)\n", dir.path());
+      test::create_file("3.md", "# Three\n\n[two](sub/2.md)\n", dir.path());
+      test::create_file("sub/2.md", "# Two\n\n[one](../1.md)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let mut backlinks = Backlinks::from_dir(&base.dir);
+      let doc = base.get_doc_mut("sub/2.md").unwrap();
+      doc.title_section.body[1].text = "[three](../3.md) and [one](../1.MD)".into();
+      doc.refresh();
+      backlinks.update_doc(&base.dir, "sub/2.md");
+      pretty::assert_eq!(backlinks, Backlinks::from_dir(&base.dir));
+      assert!(backlinks.to("1.md").is_empty());
+      assert!(backlinks.is_referenced("1.md"));
+    }
+  }
+}
@@ -3,7 +3,8 @@ use crate::check::Issue;
 use crate::config::LoadResult;
 use crate::{Config, config, fspath};
 use ahash::AHashMap;
-use camino::{Utf8DirEntry, Utf8Path};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
 use merge::Merge;
 
 pub struct Directory {
@@ -12,6 +13,19 @@ pub struct Directory {
   pub dirs: AHashMap<String, Directory>,
   pub docs: AHashMap<String, Document>,
   pub resources: AHashMap<String, ()>,
+  /// symlinks in this directory that cannot be followed
+  pub broken_symlinks: AHashMap<String, BrokenSymlink>,
+  /// problems reading the files and subdirectories of this directory
+  pub read_errors: Vec<Issue>,
+}
+
+/// a symlink that Tikibase cannot follow
+#[derive(Debug, Eq, PartialEq)]
+pub enum BrokenSymlink {
+  /// the symlink points to a non-existing file or directory
+  Dangling { target: String },
+  /// the symlink points to a directory containing it
+  Cycle { target: String },
 }
 
 impl Directory {
@@ -70,11 +84,42 @@ impl Directory {
     }
   }
 
+  /// provides the correctly capitalized path of the file or directory with the given path when ignoring capitalization,
+  /// or nothing if there is no such entry or several entries differ only in their capitalization
+  pub fn path_ignoring_case(&self, path: &str) -> Option<String> {
+    match lowest_subdir(path) {
+      ("", filename) => {
+        let names = self
+          .docs
+          .keys()
+          .chain(self.resources.keys())
+          .chain(self.dirs.keys());
+        unique_match_ignoring_case(names, filename).cloned()
+      }
+      (subdir, remaining_path) => {
+        let name = unique_match_ignoring_case(self.dirs.keys(), subdir)?;
+        let remaining = self.dirs.get(name)?.path_ignoring_case(remaining_path)?;
+        Some(format!("{name}/{remaining}"))
+      }
+    }
+  }
+
   /// provides a Directory instance for the given directory
   pub fn load(
+    root: &Utf8Path,
+    relative_path: String,
+    parent_config: Config,
+  ) -> Result<Self, Vec<Issue>> {
+    Self::load_below(root, relative_path, parent_config, &[])
+  }
+
+  /// provides a Directory instance for the given directory,
+  /// which is located inside the directories with the given canonical paths
+  fn load_below(
     root: &Utf8Path,
     relative_path: String,
     mut parent_config: Config,
+    ancestors: &[Utf8PathBuf],
   ) -> Result<Self, Vec<Issue>> {
     let abs_path = root.join(&relative_path);
     let config = match config::load(&abs_path) {
@@ -85,9 +130,19 @@ impl Directory {
       LoadResult::NotFound => parent_config,
       LoadResult::Error(issue) => return Err(vec![issue]),
     };
+    let mut ancestors = ancestors.to_vec();
+    if config.follow_symlinks() {
+      ancestors.push(
+        abs_path
+          .canonicalize_utf8()
+          .unwrap_or_else(|_| abs_path.clone()),
+      );
+    }
     let mut docs = AHashMap::new();
     let mut dirs = AHashMap::new();
     let mut resources = AHashMap::new();
+    let mut broken_symlinks = AHashMap::new();
+    let mut read_errors = Vec::new();
     let mut errors = Vec::new();
     let entries = match Utf8Path::read_dir_utf8(abs_path.as_ref()) {
       Ok(entries) => entries,
@@ -99,16 +154,60 @@ impl Directory {
       }
     };
     for entry in entries {
-      let entry = entry.unwrap();
+      let entry = match entry {
+        Ok(entry) => entry,
+        Err(err) => {
+          read_errors.push(Issue::CannotReadDirectory {
+            path: abs_path.to_string(),
+            err: err.to_string(),
+          });
+          continue;
+        }
+      };
       let entry_name = entry.file_name().to_owned(); // TODO: try using the &str directly here, instead of converting it to a String
-      match EntryType::from_direntry(&entry, &config) {
+      let mut file_type = match entry.file_type() {
+        Ok(file_type) => file_type,
+        Err(err) => {
+          read_errors.push(Issue::CannotReadFile {
+            file: fspath::join(&relative_path, &entry_name),
+            message: err.to_string(),
+          });
+          continue;
+        }
+      };
+      let is_symlink = file_type.is_symlink();
+      if is_symlink && !entry_name.starts_with('.') {
+        match fs::metadata(entry.path()) {
+          Ok(metadata) => {
+            if config.follow_symlinks() {
+              file_type = metadata.file_type();
+            }
+          }
+          Err(_) => {
+            broken_symlinks.insert(
+              entry_name,
+              BrokenSymlink::Dangling {
+                target: link_target(entry.path()),
+              },
+            );
+            continue;
+          }
+        }
+      }
+      match EntryType::from_file_type(
+        &entry_name,
+        file_type.is_file(),
+        file_type.is_dir(),
+        &config,
+      ) {
         EntryType::Document => {
           let doc_relative_path = fspath::join(&relative_path, &entry_name);
           match Document::load(entry.path(), doc_relative_path) {
             Ok(doc) => {
               docs.insert(entry_name, doc);
             }
-            Err(err) => errors.push(err),
+            Err(issue @ Issue::CannotReadFile { .. }) => read_errors.push(issue),
+            Err(issue) => errors.push(issue),
           }
         }
         EntryType::Resource => {
@@ -116,14 +215,39 @@ impl Directory {
         }
         EntryType::Configuration | EntryType::Ignored => continue,
         EntryType::Directory => {
-          dirs.insert(
-            entry_name.clone(),
-            Self::load(
-              root,
-              fspath::join(&relative_path, &entry_name),
-              config.clone(),
-            )?,
-          );
+          if is_symlink {
+            let target = entry.path().canonicalize_utf8();
+            if target
+              .as_ref()
+              .is_ok_and(|target| ancestors.contains(target))
+            {
+              broken_symlinks.insert(
+                entry_name,
+                BrokenSymlink::Cycle {
+                  target: link_target(entry.path()),
+                },
+              );
+              continue;
+            }
+          }
+          match Self::load_below(
+            root,
+            fspath::join(&relative_path, &entry_name),
+            config.clone(),
+            &ancestors,
+          ) {
+            Ok(dir) => {
+              dirs.insert(entry_name, dir);
+            }
+            Err(issues) => {
+              for issue in issues {
+                match issue {
+                  Issue::CannotReadDirectory { .. } => read_errors.push(issue),
+                  issue => errors.push(issue),
+                }
+              }
+            }
+          }
         }
       }
     }
@@ -134,6 +258,8 @@ impl Directory {
         dirs,
         docs,
         resources,
+        broken_symlinks,
+        read_errors,
       })
     } else {
       Err(errors)
@@ -157,13 +283,12 @@ pub enum EntryType {
 }
 
 impl EntryType {
-  fn from_direntry(entry: &Utf8DirEntry, config: &Config) -> Self {
-    let entry_type = entry.file_type().unwrap();
-    let entry_filename = entry.file_name();
+  /// provides the type of the filesystem entry with the given name that is a file or directory
+  fn from_file_type(entry_filename: &str, is_file: bool, is_dir: bool, config: &Config) -> Self {
     if entry_filename.starts_with('.') {
       return Self::Ignored;
     }
-    if entry_type.is_file() {
+    if is_file {
       if entry_filename == "tikibase.json" {
         return Self::Configuration;
       }
@@ -175,7 +300,7 @@ impl EntryType {
       }
       return Self::Resource;
     }
-    if entry_type.is_dir() {
+    if is_dir {
       return Self::Directory;
     }
     Self::Ignored
@@ -198,12 +323,34 @@ impl EntryType {
   }
 }
 
+/// provides the path that the symlink at the given path points to
+fn link_target(path: &Utf8Path) -> String {
+  match fs::read_link(path) {
+    Ok(target) => target.to_string_lossy().into_owned(),
+    Err(err) => err.to_string(),
+  }
+}
+
 /// case-insensitive comparison of file extensions
 fn has_extension(path: &str, given_ext: &str) -> bool {
   let path_ext = path.rsplit('.').next().unwrap();
   path_ext.eq_ignore_ascii_case(given_ext)
 }
 
+/// provides the only one of the given names that matches the given name when ignoring capitalization
+fn unique_match_ignoring_case<'a, I: Iterator<Item = &'a String>>(
+  names: I,
+  name: &str,
+) -> Option<&'a String> {
+  let name = name.to_lowercase();
+  let mut matches = names.filter(|candidate| candidate.to_lowercase() == name);
+  let first = matches.next()?;
+  match matches.next() {
+    Some(_) => None,
+    None => Some(first),
+  }
+}
+
 /// provides the lowest subdirectory portion of the given path
 /// If a subdir was found, removes it from the given path.
 fn lowest_subdir(path: &str) -> (&str, &str) {
@@ -375,6 +522,71 @@ mod tests {
     assert_eq!(dir.resources.len(), 0);
   }
 
+  #[cfg(unix)]
+  mod load_symlinks {
+    use crate::database::{BrokenSymlink, Directory};
+    use crate::{Config, test};
+    use big_s::S;
+
+    #[test]
+    fn ignored_by_default() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("shared/one.md", "# One\n", dir.path());
+      test::create_symlink("base/one.md", "../shared/one.md", dir.path());
+      test::create_symlink("base/missing.md", "../shared/missing.md", dir.path());
+      let base = Directory::load(&dir.path().join("base"), S(""), Config::default()).unwrap();
+      assert!(base.docs.is_empty());
+      let want = BrokenSymlink::Dangling {
+        target: S("../shared/missing.md"),
+      };
+      assert_eq!(base.broken_symlinks.get("missing.md"), Some(&want));
+      assert_eq!(base.broken_symlinks.len(), 1);
+    }
+
+    #[test]
+    fn follows_files_and_directories() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "base/tikibase.json",
+        "{ \"followSymlinks\": true }",
+        dir.path(),
+      );
+      test::create_file("shared/one.md", "# One\n", dir.path());
+      test::create_file("shared/notes/two.md", "# Two\n", dir.path());
+      test::create_symlink("base/one.md", "../shared/one.md", dir.path());
+      test::create_symlink("base/notes", "../shared/notes", dir.path());
+      let base = Directory::load(&dir.path().join("base"), S(""), Config::default()).unwrap();
+      assert!(base.get_doc("one.md").is_some());
+      assert!(base.get_doc("notes/two.md").is_some());
+      assert!(base.broken_symlinks.is_empty());
+    }
+
+    #[test]
+    fn dangling() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("tikibase.json", "{ \"followSymlinks\": true }", dir.path());
+      test::create_symlink("missing.md", "other.md", dir.path());
+      let base = Directory::load(dir.path(), S(""), Config::default()).unwrap();
+      let want = BrokenSymlink::Dangling {
+        target: S("other.md"),
+      };
+      assert_eq!(base.broken_symlinks.get("missing.md"), Some(&want));
+    }
+
+    #[test]
+    fn cycle() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("tikibase.json", "{ \"followSymlinks\": true }", dir.path());
+      test::create_file("sub/one.md", "# One\n", dir.path());
+      test::create_symlink("sub/loop", "..", dir.path());
+      let base = Directory::load(dir.path(), S(""), Config::default()).unwrap();
+      let sub = base.get_dir("sub").unwrap();
+      assert!(sub.get_doc("one.md").is_some());
+      let want = BrokenSymlink::Cycle { target: S("..") };
+      assert_eq!(sub.broken_symlinks.get("loop"), Some(&want));
+    }
+  }
+
   mod lowest_subdir {
 
     #[test]
@@ -401,4 +613,41 @@ mod tests {
       assert_eq!(have, want);
     }
   }
+
+  mod path_ignoring_case {
+    use crate::database::Directory;
+    use crate::{Config, test};
+    use big_s::S;
+
+    #[test]
+    fn different_case() {
+      let root = camino_tempfile::tempdir().unwrap();
+      test::create_file("Sub/One.md", "# One", root.path());
+      test::create_file("Sub/image.PNG", "image", root.path());
+      let dir = Directory::load(root.path(), S(""), Config::default()).unwrap();
+      assert_eq!(dir.path_ignoring_case("sub/one.md"), Some(S("Sub/One.md")));
+      assert_eq!(
+        dir.path_ignoring_case("SUB/IMAGE.png"),
+        Some(S("Sub/image.PNG"))
+      );
+      assert_eq!(dir.path_ignoring_case("sub"), Some(S("Sub")));
+    }
+
+    #[test]
+    fn missing() {
+      let root = camino_tempfile::tempdir().unwrap();
+      test::create_file("one.md", "# One", root.path());
+      let dir = Directory::load(root.path(), S(""), Config::default()).unwrap();
+      assert_eq!(dir.path_ignoring_case("two.md"), None);
+    }
+
+    #[test]
+    fn ambiguous() {
+      let root = camino_tempfile::tempdir().unwrap();
+      test::create_file("one.md", "# One", root.path());
+      test::create_file("ONE.md", "# One", root.path());
+      let dir = Directory::load(root.path(), S(""), Config::default()).unwrap();
+      assert_eq!(dir.path_ignoring_case("One.md"), None);
+    }
+  }
 }
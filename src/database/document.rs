@@ -1,12 +1,16 @@
-use super::{Footnotes, Image, Line, Link, Section, section};
+use super::{
+  FileFormat, Footnotes, Image, Line, Link, LinkReferences, Markdown, Section, section, windows1252,
+};
 use crate::check::{Issue, Location};
-use camino::Utf8Path;
+use crate::config::SlugStyle;
+use crate::fspath;
+use camino::{Utf8Path, Utf8PathBuf};
+use core::iter;
+use core::ops::RangeInclusive;
 use fs_err as fs;
-use fs_err::File;
-use std::io::BufReader;
 use std::io::prelude::*;
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Document {
   /// the path relative to the Tikibase root directory
   pub relative_path: String,
@@ -19,58 +23,133 @@ pub struct Document {
   // TODO: convert to HashSet and use https://github.com/mcarton/rust-derivative to ignore this when hashing Document
   pub links: Vec<Link>,
   pub images: Vec<Image>,
+  /// cache of the anchors that HTML tags in this document define
+  pub html_anchors: Vec<String>,
+
+  /// the line containing the first byte that isn't valid UTF-8,
+  /// if this document was read as Windows-1252 because it isn't valid UTF-8
+  pub non_utf8_line: Option<u32>,
+
+  /// how the file of this document stores line endings, byte order mark, and final newline
+  pub format: FileFormat,
 }
 
 impl Document {
-  pub fn contains_reference_to(&self, path: &str) -> bool {
-    self.links.iter().any(|r| r.points_to(path)) || self.images.iter().any(|r| r.points_to(path))
+  /// indicates whether this document links to or embeds the file with the given path relative to this document,
+  /// root-relative links start at the given base path
+  pub fn contains_reference_to(&self, path: &str, base_path: Option<&str>) -> bool {
+    self.contains_reference_where(path, base_path, |_line| true)
+  }
+
+  /// indicates whether this document references the given path outside of the section with the given title
+  pub fn contains_reference_outside_section(
+    &self,
+    path: &str,
+    section_title: &str,
+    base_path: Option<&str>,
+  ) -> bool {
+    let section_lines = self
+      .section_with_title(section_title)
+      .map(|section| section.line_number..=section.last_line_abs());
+    self.contains_reference_where(path, base_path, |line| {
+      !section_lines
+        .as_ref()
+        .is_some_and(|lines| lines.contains(&line))
+    })
+  }
+
+  /// indicates whether this document references the given path on a line matching the given predicate
+  fn contains_reference_where<F: Fn(u32) -> bool>(
+    &self,
+    path: &str,
+    base_path: Option<&str>,
+    on_line: F,
+  ) -> bool {
+    let dir = fspath::dirname(&self.relative_path);
+    let target = fspath::normalize(&fspath::join(dir, path));
+    let points_to_target = |reference: &str| {
+      let file = match reference.split_once('#') {
+        Some((file, _anchor)) => file,
+        None => reference,
+      };
+      !file.is_empty()
+        && fspath::resolve(dir, file, base_path).is_some_and(|resolved| resolved == target)
+    };
+    self
+      .links
+      .iter()
+      .any(|link| on_line(link.line) && points_to_target(&link.target))
+      || self
+        .images
+        .iter()
+        .any(|image| on_line(image.line) && points_to_target(&image.src))
   }
 
   /// provides all the footnotes that this document defines and references
   pub fn footnotes(&self) -> Result<Footnotes, Issue> {
     let mut result = Footnotes::default();
-    let mut code_block_start: Option<CodeblockStart> = None;
+    let code_blocks = Markdown::new(
+      self
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i as u32, line.text.as_str())),
+    )
+    .code_blocks();
     for (i, line) in self.lines().enumerate() {
-      if line.is_code_block_boundary() {
-        code_block_start = match code_block_start {
-          Some(_) => None,
-          None => Some(CodeblockStart {
-            line: i as u32,
-            len: line.text.len() as u32,
-          }),
-        };
+      let i = i as u32;
+      if code_blocks
+        .iter()
+        .any(|code_block| (code_block.start..=code_block.end).contains(&i))
+      {
         continue;
       }
-      if code_block_start.is_none() {
-        line.add_footnotes_to(&mut result, &self.relative_path, i as u32)?;
-      }
-    }
-    if let Some(code_block_start) = code_block_start {
-      return Err(Issue::UnclosedFence {
-        location: Location {
-          file: self.relative_path.clone(),
-          line: code_block_start.line,
-          start: 0,
-          end: code_block_start.len,
-        },
-      });
+      line.add_footnotes_to(&mut result, &self.relative_path, i)?;
     }
     Ok(result)
   }
 
+  /// provides the lines of the footnote definitions that start a line in this document,
+  /// including their indented continuation lines,
+  /// in the order in which they should appear at the end of this document
+  pub fn footnote_definition_blocks(&self, footnotes: &Footnotes) -> Vec<RangeInclusive<u32>> {
+    let lines: Vec<&str> = self.lines().map(|line| line.text.as_str()).collect();
+    footnotes
+      .definitions_in_reference_order()
+      .into_iter()
+      .filter(|definition| definition.start == 0)
+      .map(|definition| {
+        let continuation = lines[definition.line as usize + 1..]
+          .iter()
+          .take_while(|line| !line.trim().is_empty() && line.starts_with([' ', '\t']))
+          .count();
+        definition.line..=definition.line + continuation as u32
+      })
+      .collect()
+  }
+
   /// provides a Document instance containing the given text
   pub fn from_lines<T, IS: Into<String>>(lines: T, relative_path: IS) -> Result<Self, Issue>
   where
     T: Iterator<Item = String>,
   {
     let relative_path = relative_path.into();
+    let lines: Vec<String> = lines.collect();
+    let markdown = Markdown::new(
+      lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (i as u32, line.as_str())),
+    );
+    let unclosed_fence = markdown
+      .code_blocks()
+      .into_iter()
+      .find(|code_block| !code_block.closed);
+    let mut headings = markdown.headings().into_iter().peekable();
     let mut sections: Vec<Section> = Vec::new();
     let mut section_builder: Option<section::Builder> = None;
-    let mut inside_fence = false;
-    let mut fence_line = 0;
     let mut old_occurrences_section: Option<Section> = None;
-    for (line_number, line) in lines.enumerate() {
-      if line.starts_with('#') && !inside_fence {
+    for (line_number, line) in lines.into_iter().enumerate() {
+      if let Some(heading) = headings.next_if(|heading| heading.line == line_number as u32) {
         if let Some(section_builder) = section_builder {
           let section = section_builder.result();
           if section.human_title() == "occurrences" {
@@ -79,13 +158,13 @@ impl Document {
             sections.push(section);
           }
         }
-        section_builder = Some(section::Builder::new(line, line_number as u32));
+        section_builder = Some(if heading.setext {
+          section::Builder::setext(line, line_number as u32, heading.level)
+        } else {
+          section::Builder::new(line, line_number as u32)
+        });
         continue;
       }
-      if line.starts_with("```") {
-        inside_fence = !inside_fence;
-        fence_line = line_number;
-      }
       match &mut section_builder {
         Some(section_builder) => section_builder.add_line(line),
         None => {
@@ -115,11 +194,11 @@ impl Document {
         });
       }
     }
-    if inside_fence {
+    if let Some(code_block) = unclosed_fence {
       return Err(Issue::UnclosedFence {
         location: Location {
           file: relative_path,
-          line: (fence_line as u32),
+          line: code_block.start,
           start: 0,
           end: 0,
         },
@@ -134,27 +213,75 @@ impl Document {
     ))
   }
 
-  /// provides the Document contained in the file with the given path
-  pub fn from_reader<IS: Into<String>, BR: BufRead>(
-    reader: BR,
-    relative_path: IS,
-  ) -> Result<Self, Issue> {
-    let lines = reader.lines().map(Result::unwrap);
-    Self::from_lines(lines, relative_path)
-  }
-
   #[cfg(test)]
   /// provides Document instances in tests
   pub fn from_str<IS: Into<String>>(path: IS, text: &str) -> Result<Self, Issue> {
     Self::from_lines(text.lines().map(str::to_owned), path)
   }
 
+  /// provides all anchors that links can point to in this document:
+  /// the anchors of all headings, with GitHub's numeric suffixes for duplicate headings,
+  /// and the ids and names of HTML tags
+  pub fn anchors(&self, style: SlugStyle) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    for section in self.sections() {
+      let anchor = match section.custom_id() {
+        Some(id) => format!("#{id}"),
+        None => section::unique_anchor(section.anchor(style), &result),
+      };
+      result.push(anchor);
+    }
+    result.extend(self.html_anchors.iter().cloned());
+    result
+  }
+
   /// indicates whether this document contains the given anchor
-  pub fn has_anchor(&self, anchor: &str) -> bool {
+  pub fn has_anchor(&self, anchor: &str, style: SlugStyle) -> bool {
+    self
+      .anchors(style)
+      .iter()
+      .any(|existing| existing == anchor)
+  }
+
+  /// provides how many sections at the end of this document Tikibase generates:
+  /// the backlinks section with the given title and the "occurrences" section
+  pub fn generated_sections_at_end(&self, backlinks_section: Option<&str>) -> usize {
     self
       .content_sections
       .iter()
-      .any(|section| section.anchor() == anchor)
+      .rev()
+      .take_while(|section| {
+        section.human_title() == "occurrences"
+          || backlinks_section.is_some_and(|title| section.title_line.text == title)
+      })
+      .count()
+  }
+
+  /// indicates whether the given footnote definition blocks are at the end of this document,
+  /// before the sections that Tikibase generates, in the given order
+  pub fn has_footnote_definitions_at_end(
+    &self,
+    blocks: &[RangeInclusive<u32>],
+    backlinks_section: Option<&str>,
+  ) -> bool {
+    let lines: Vec<&str> = self.lines().map(|line| line.text.as_str()).collect();
+    let generated = self.generated_sections_at_end(backlinks_section);
+    let mut end = match generated {
+      0 => lines.len(),
+      generated => {
+        self.content_sections[self.content_sections.len() - generated].line_number as usize
+      }
+    };
+    for block in blocks.iter().rev() {
+      while end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+      }
+      if *block.end() as usize + 1 != end {
+        return false;
+      }
+      end = *block.start() as usize;
+    }
+    true
   }
 
   /// provides the human-readable title of this document
@@ -193,6 +320,26 @@ impl Document {
     }
   }
 
+  /// provides all link reference definitions and reference-style links in this document
+  pub fn link_references(&self) -> LinkReferences {
+    Markdown::new(self.sections().flat_map(Section::numbered_lines)).link_references()
+  }
+
+  /// provides the line with the given line number for modification
+  pub fn line_mut(&mut self, line_number: u32) -> Option<&mut Line> {
+    let section = iter::once(&mut self.title_section)
+      .chain(&mut self.content_sections)
+      .find(|section| {
+        section.line_number <= line_number && line_number <= section.last_line_abs()
+      })?;
+    if line_number == section.line_number {
+      return Some(&mut section.title_line);
+    }
+    section
+      .body
+      .get_mut((line_number - section.line_number - 1) as usize)
+  }
+
   /// provides the number of lines in this document
   pub fn lines_count(&self) -> u32 {
     self
@@ -202,9 +349,43 @@ impl Document {
       .last_line_abs()
   }
 
+  /// loads the document at the given path,
+  /// reads it as Windows-1252 if it isn't valid UTF-8
   pub fn load<P: AsRef<Utf8Path>>(absolute_path: P, relative_path: String) -> Result<Self, Issue> {
-    let file = File::open(absolute_path.as_ref()).unwrap();
-    Self::from_reader(BufReader::new(file), relative_path)
+    let bytes = match fs::read(absolute_path.as_ref()) {
+      Ok(bytes) => bytes,
+      Err(err) => {
+        return Err(Issue::CannotReadFile {
+          file: relative_path,
+          message: err.to_string(),
+        });
+      }
+    };
+    match String::from_utf8(bytes) {
+      Ok(content) => {
+        let (format, text) = FileFormat::detect(&content);
+        let mut doc = Self::from_lines(text.lines().map(str::to_owned), relative_path)?;
+        doc.format = format;
+        Ok(doc)
+      }
+      Err(err) => {
+        let valid_up_to = err.utf8_error().valid_up_to();
+        let bytes = err.into_bytes();
+        let line = bytes[..valid_up_to]
+          .iter()
+          .filter(|byte| **byte == b'\n')
+          .count();
+        let content = windows1252::decode(&bytes);
+        let (format, text) = FileFormat::detect(&content);
+        let mut doc = Self::from_lines(text.lines().map(str::to_owned), relative_path)?;
+        doc.non_utf8_line = Some(line as u32);
+        doc.format = FileFormat {
+          windows1252: true,
+          ..format
+        };
+        Ok(doc)
+      }
+    }
   }
 
   pub fn new(
@@ -214,6 +395,7 @@ impl Document {
     old_occurrences_section: Option<Section>,
   ) -> Self {
     let (links, images) = Self::references(&title_section, &content_sections);
+    let html_anchors = Self::html_anchors(&title_section, &content_sections);
     Self {
       relative_path: path,
       title_section,
@@ -221,6 +403,9 @@ impl Document {
       old_occurrences_section,
       links,
       images,
+      html_anchors,
+      non_utf8_line: None,
+      format: FileFormat::default(),
     }
   }
 
@@ -230,17 +415,60 @@ impl Document {
   ) -> (Vec<Link>, Vec<Image>) {
     let mut links = vec![];
     let mut images = vec![];
-    title_section.references(&mut links, &mut images);
-    for section in content_sections {
-      section.references(&mut links, &mut images);
-    }
+    let lines = iter::once(title_section)
+      .chain(content_sections)
+      .flat_map(Section::numbered_lines);
+    Markdown::new(lines).references(&mut links, &mut images);
     (links, images)
   }
 
-  /// persists the changes made to this document to disk
-  pub fn save(&self, root: &Utf8Path) {
-    let mut file = fs::File::create(root.join(&self.relative_path)).unwrap();
-    file.write_all(self.text().as_bytes()).unwrap();
+  /// provides the anchors that HTML tags in the given sections define via id or name attributes
+  fn html_anchors(title_section: &Section, content_sections: &[Section]) -> Vec<String> {
+    let lines = iter::once(title_section)
+      .chain(content_sections)
+      .flat_map(Section::numbered_lines);
+    Markdown::new(lines).html_anchors()
+  }
+
+  /// updates the data derived from the sections of this document after they changed:
+  /// the line numbers of the sections and the caches of links, images, and HTML anchors
+  pub fn refresh(&mut self) {
+    let mut line_number = self.title_section.last_line_abs() + 1;
+    for section in &mut self.content_sections {
+      section.line_number = line_number;
+      line_number = section.last_line_abs() + 1;
+    }
+    (self.links, self.images) = Self::references(&self.title_section, &self.content_sections);
+    self.html_anchors = Self::html_anchors(&self.title_section, &self.content_sections);
+  }
+
+  /// removes the line with the given line number, section title lines don't get removed
+  pub fn remove_line(&mut self, line_number: u32) {
+    let Some(section) = iter::once(&mut self.title_section)
+      .chain(&mut self.content_sections)
+      .find(|section| section.line_number < line_number && line_number <= section.last_line_abs())
+    else {
+      return;
+    };
+    section
+      .body
+      .remove((line_number - section.line_number - 1) as usize);
+  }
+
+  /// persists the changes made to this document to disk,
+  /// replaces the file only after the new content is completely written
+  pub fn save(&self, root: &Utf8Path) -> Result<(), Issue> {
+    let content = self
+      .format
+      .encode(&self.text())
+      .map_err(|c| Issue::CannotWriteFile {
+        file: self.relative_path.clone(),
+        message: format!("cannot store \"{c}\" in Windows-1252, convert the file to UTF-8 first"),
+      })?;
+    replace_file(&root.join(&self.relative_path), &content).map_err(|err| Issue::CannotWriteFile {
+      file: self.relative_path.clone(),
+      message: err.to_string(),
+    })
   }
 
   /// provides a non-consuming iterator for all sections in this document
@@ -277,6 +505,14 @@ impl Document {
       .find(|section| section.human_title() == human_title)
   }
 
+  /// provides the section with the given title
+  pub fn section_with_title(&self, title: &str) -> Option<&Section> {
+    self
+      .content_sections
+      .iter()
+      .find(|section| section.title_line.text == title)
+  }
+
   /// provides the section with the given title
   pub fn section_with_title_mut(&mut self, title: &str) -> Option<&mut Section> {
     self
@@ -295,6 +531,33 @@ impl Document {
   }
 }
 
+/// replaces the content of the file at the given path with the given content,
+/// replaces the file only after the new content is completely written
+pub(super) fn replace_file(path: &Utf8Path, content: &[u8]) -> std::io::Result<()> {
+  // write through symlinks instead of replacing them
+  let path = path.canonicalize_utf8().unwrap_or_else(|_| path.to_owned());
+  let temp_path = match (path.parent(), path.file_name()) {
+    (Some(dir), Some(name)) => dir.join(format!(".{name}.tikibase-tmp")),
+    _ => Utf8PathBuf::from(format!("{path}.tikibase-tmp")),
+  };
+  let result = write_file(&temp_path, content, &path).and_then(|()| fs::rename(&temp_path, &path));
+  if result.is_err() {
+    let _ = fs::remove_file(&temp_path);
+  }
+  result
+}
+
+/// writes the given content into a new file at the given path,
+/// with the permissions of the given existing file
+fn write_file(path: &Utf8Path, content: &[u8], existing: &Utf8Path) -> std::io::Result<()> {
+  let mut file = fs::File::create(path)?;
+  file.write_all(content)?;
+  if let Ok(metadata) = fs::metadata(existing) {
+    file.set_permissions(metadata.permissions())?;
+  }
+  file.sync_all()
+}
+
 /// iterates all sections of a document
 pub struct SectionIterator<'a> {
   title_section: &'a Section,
@@ -336,14 +599,6 @@ impl<'a> Iterator for LinesIterator<'a> {
   }
 }
 
-/// describes the start of a codeblock
-struct CodeblockStart {
-  /// the line on which this codeblock starts
-  line: u32,
-  /// length of the text on this line
-  len: u32,
-}
-
 // -------------------------------------------------------------------------------------
 // TESTS
 // -------------------------------------------------------------------------------------
@@ -351,6 +606,7 @@ struct CodeblockStart {
 #[cfg(test)]
 mod tests {
   use super::Document;
+  use crate::config::SlugStyle;
   use crate::database::{Image, Link};
   use big_s::S;
   use indoc::indoc;
@@ -366,7 +622,7 @@ mod tests {
       test::create_file("sub/two.md", "# Two\n[one](../one.md)", dir.path());
       let base = Tikibase::load(dir.path()).unwrap();
       let doc = base.get_doc("one.md").unwrap();
-      assert!(doc.contains_reference_to("sub/two.md"));
+      assert!(doc.contains_reference_to("sub/two.md", None));
     }
 
     #[test]
@@ -376,7 +632,7 @@ mod tests {
       test::create_file("sub/two.md", "# Two\n[one](../one.md)", dir.path());
       let base = Tikibase::load(dir.path()).unwrap();
       let doc = base.get_doc("sub/two.md").unwrap();
-      assert!(doc.contains_reference_to("../one.md"));
+      assert!(doc.contains_reference_to("../one.md", None));
     }
 
     #[test]
@@ -386,7 +642,38 @@ mod tests {
       test::create_file("sub/two.md", "# Two\n[one](../one.md#section)", dir.path());
       let base = Tikibase::load(dir.path()).unwrap();
       let doc = base.get_doc("one.md").unwrap();
-      assert!(doc.contains_reference_to("sub/two.md"));
+      assert!(doc.contains_reference_to("sub/two.md", None));
+    }
+
+    #[test]
+    fn root_relative() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("one.md", "# One\n[two](sub/two.md)", dir.path());
+      test::create_file("sub/two.md", "# Two\n[one](/wiki/one.md)", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let doc = base.get_doc("sub/two.md").unwrap();
+      assert!(doc.contains_reference_to("../one.md", Some("/wiki")));
+      assert!(!doc.contains_reference_to("../one.md", None));
+    }
+  }
+
+  mod contains_reference_outside_section {
+    use crate::database::Document;
+    use indoc::indoc;
+
+    #[test]
+    fn reference_in_other_section() {
+      let give = indoc! {"
+        # One
+        [two](2.md)
+
+        ### backlinks
+
+        - [three](3.md)
+        "};
+      let doc = Document::from_str("1.md", give).unwrap();
+      assert!(doc.contains_reference_outside_section("2.md", "### backlinks", None));
+      assert!(!doc.contains_reference_outside_section("3.md", "### backlinks", None));
     }
   }
 
@@ -469,6 +756,37 @@ mod tests {
       pretty::assert_eq!(have, want);
     }
 
+    #[test]
+    fn tilde_and_nested_code_blocks() {
+      let give = indoc! {"
+                # Title
+                ~~~
+                [^1]
+                ~~~
+                ````md
+                ```
+                [^2]
+                ```
+                [^3]
+                ````
+                "};
+      let have = Document::from_str("test.md", give).unwrap().footnotes();
+      let want = Ok(Footnotes::default());
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn indented_code_block() {
+      let give = indoc! {"
+                # Title
+
+                    [^1]
+                "};
+      let have = Document::from_str("test.md", give).unwrap().footnotes();
+      let want = Ok(Footnotes::default());
+      pretty::assert_eq!(have, want);
+    }
+
     #[test]
     fn code_segment() {
       let give = indoc! {"
@@ -484,7 +802,7 @@ mod tests {
   mod from_str {
     use super::super::Document;
     use crate::check::{Issue, Location};
-    use crate::database::{Line, Section};
+    use crate::database::{FileFormat, Line, Link, Section};
     use big_s::S;
     use indoc::indoc;
 
@@ -514,6 +832,51 @@ mod tests {
         old_occurrences_section: None,
         links: vec![],
         images: vec![],
+        html_anchors: vec![],
+        non_utf8_line: None,
+        format: FileFormat::default(),
+      });
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn setext_titles_and_hashtags() {
+      let give = indoc! {"
+                Test
+                ====
+                #hashtag
+
+                Section 1
+                ---------
+                [one](one.md)"};
+      let have = Document::from_str("one.md", give);
+      let want = Ok(Document {
+        relative_path: S("one.md"),
+        title_section: Section {
+          line_number: 0,
+          title_line: Line::from("Test"),
+          body: vec![Line::from("===="), Line::from("#hashtag"), Line::from("")],
+          title_text_start: 0,
+          level: 1,
+        },
+        content_sections: vec![Section {
+          line_number: 4,
+          title_line: Line::from("Section 1"),
+          body: vec![Line::from("---------"), Line::from("[one](one.md)")],
+          title_text_start: 0,
+          level: 2,
+        }],
+        old_occurrences_section: None,
+        links: vec![Link {
+          target: S("one.md"),
+          line: 6,
+          start: 0,
+          end: 13,
+        }],
+        images: vec![],
+        html_anchors: vec![],
+        non_utf8_line: None,
+        format: FileFormat::default(),
       });
       pretty::assert_eq!(have, want);
     }
@@ -560,6 +923,9 @@ mod tests {
         old_occurrences_section: None,
         links: vec![],
         images: vec![],
+        html_anchors: vec![],
+        non_utf8_line: None,
+        format: FileFormat::default(),
       });
       pretty::assert_eq!(have, want);
     }
@@ -584,6 +950,47 @@ mod tests {
       pretty::assert_eq!(have, want);
     }
 
+    #[test]
+    fn open_nested_fenced_code_block() {
+      let give = indoc! {"
+                # test
+                text
+                ````md
+                ```
+                ### not a document section
+                ```
+                "};
+      let have = Document::from_str("test.md", give);
+      let want = Err(Issue::UnclosedFence {
+        location: Location {
+          file: S("test.md"),
+          line: 2,
+          start: 0,
+          end: 0,
+        },
+      });
+      pretty::assert_eq!(have, want);
+    }
+
+    #[test]
+    fn headings_in_code_blocks() {
+      let give = indoc! {"
+                # test
+                ~~~
+                ### tilde fence
+                ~~~
+                ````md
+                ```
+                ### nested fence
+                ```
+                ````
+
+                    ### indented code
+                "};
+      let have = Document::from_str("test.md", give).unwrap();
+      assert!(have.content_sections.is_empty());
+    }
+
     #[test]
     fn with_occurrences_section() {
       let give = indoc! {"
@@ -629,17 +1036,67 @@ mod tests {
         }),
         links: vec![],
         images: vec![],
+        html_anchors: vec![],
+        non_utf8_line: None,
+        format: FileFormat::default(),
       });
       pretty::assert_eq!(have, want);
     }
   }
 
+  #[test]
+  fn anchors() {
+    let give = indoc! {r#"
+      # Title
+
+      ### Details
+      ### Details
+      ### Details {#explicit}
+      ### Details
+      text <a id="html-id"></a> and <a name="html-name">
+
+      <div id="block-id">
+      </div>
+      "#};
+    let doc = Document::from_str("test.md", give).unwrap();
+    let have = doc.anchors(SlugStyle::Kebab);
+    let want = vec![
+      "#title",
+      "#details",
+      "#details-1",
+      "#explicit",
+      "#details-2",
+      "#html-id",
+      "#html-name",
+      "#block-id",
+    ];
+    pretty::assert_eq!(have, want);
+  }
+
   #[test]
   fn has_anchor() {
     let doc = Document::from_str("test.md", "# Title\n\n## head 1\ntext\n### head 2\n").unwrap();
-    assert!(doc.has_anchor("#head-1"));
-    assert!(doc.has_anchor("#head-2"));
-    assert!(!doc.has_anchor("#head-3"));
+    assert!(doc.has_anchor("#head-1", SlugStyle::Kebab));
+    assert!(doc.has_anchor("#head-2", SlugStyle::Kebab));
+    assert!(!doc.has_anchor("#head-3", SlugStyle::Kebab));
+  }
+
+  #[test]
+  fn has_anchor_after_refresh() {
+    let mut doc = Document::from_str("test.md", "# Title\n\ntext\n").unwrap();
+    assert!(!doc.has_anchor("#html-id", SlugStyle::Kebab));
+    doc.line_mut(2).unwrap().text = S(r#"<a id="html-id"></a>"#);
+    doc.refresh();
+    assert!(doc.has_anchor("#html-id", SlugStyle::Kebab));
+  }
+
+  #[test]
+  fn generated_sections_at_end() {
+    let content = "# Title\n\n### notes\n\n### backlinks\n";
+    let doc = Document::from_str("test.md", content).unwrap();
+    assert_eq!(doc.generated_sections_at_end(Some("### backlinks")), 1);
+    assert_eq!(doc.generated_sections_at_end(Some("### notes")), 0);
+    assert_eq!(doc.generated_sections_at_end(None), 0);
   }
 
   mod last_line {
@@ -794,6 +1251,161 @@ mod tests {
     }
   }
 
+  mod load {
+    use crate::check::Issue;
+    use crate::database::Document;
+    use crate::test;
+    use fs_err as fs;
+
+    #[test]
+    fn latin1() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      fs::write(dir.path().join("1.md"), b"# One\n\ncaf\xe9\n").unwrap();
+      let have = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      assert_eq!(have.non_utf8_line, Some(2));
+      assert_eq!(have.text(), "# One\n\ncaf\u{e9}\n");
+    }
+
+    #[test]
+    fn windows1252() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      fs::write(dir.path().join("1.md"), b"# One\n\n\x93quoted\x94 \x80\n").unwrap();
+      let have = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      assert_eq!(have.non_utf8_line, Some(2));
+      assert!(have.format.windows1252);
+      assert_eq!(have.text(), "# One\n\n\u{201c}quoted\u{201d} \u{20ac}\n");
+    }
+
+    #[test]
+    fn line_endings_bom_and_final_newline() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = "\u{feff}# One\r\n\r\n### section\r\n\r\ntext";
+      fs::write(dir.path().join("1.md"), content).unwrap();
+      let mut doc = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      assert_eq!(doc.title_section.title_line.text, "# One");
+      doc.content_sections[0].title_line.text = "### Section".into();
+      doc.save(dir.path()).unwrap();
+      let have = fs::read_to_string(dir.path().join("1.md")).unwrap();
+      assert_eq!(have, "\u{feff}# One\r\n\r\n### Section\r\n\r\ntext");
+    }
+
+    #[test]
+    fn missing_file() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let have = Document::load(dir.path().join("1.md"), "1.md".into());
+      assert!(matches!(have, Err(Issue::CannotReadFile { file, message: _ }) if file == "1.md"));
+    }
+
+    #[test]
+    fn utf8() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\ncaf\u{e9}\n", dir.path());
+      let have = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      assert_eq!(have.non_utf8_line, None);
+    }
+  }
+
+  mod save {
+    use crate::check::Issue;
+    use crate::database::Document;
+    use crate::test;
+    use big_s::S;
+    use fs_err as fs;
+
+    #[test]
+    fn replaces_file() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\nold\n", dir.path());
+      let doc = Document::from_str("1.md", "# One\n\nnew\n").unwrap();
+      doc.save(dir.path()).unwrap();
+      assert_eq!(test::load_file("1.md", dir.path()), "# One\n\nnew\n");
+      let files = fs::read_dir(dir.path()).unwrap().count();
+      assert_eq!(files, 1);
+    }
+
+    #[test]
+    fn windows1252() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      fs::write(dir.path().join("1.md"), b"# One\n\n\x93caf\xe9\x94\n").unwrap();
+      let mut doc = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      doc.title_section.title_line.text = S("# \u{201e}One\u{201c}");
+      doc.save(dir.path()).unwrap();
+      let have = fs::read(dir.path().join("1.md")).unwrap();
+      assert_eq!(have, b"# \x84One\x93\n\n\x93caf\xe9\x94\n");
+    }
+
+    #[test]
+    fn windows1252_unrepresentable() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      fs::write(dir.path().join("1.md"), b"# One\n\ncaf\xe9\n").unwrap();
+      let mut doc = Document::load(dir.path().join("1.md"), "1.md".into()).unwrap();
+      doc.title_section.title_line.text = S("# One \u{2192} Two");
+      let Err(Issue::CannotWriteFile { file, message }) = doc.save(dir.path()) else {
+        panic!("saved");
+      };
+      assert_eq!(file, "1.md");
+      assert_eq!(
+        message,
+        "cannot store \"\u{2192}\" in Windows-1252, convert the file to UTF-8 first"
+      );
+      assert_eq!(
+        fs::read(dir.path().join("1.md")).unwrap(),
+        b"# One\n\ncaf\xe9\n"
+      );
+    }
+
+    #[test]
+    fn write_error() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let doc = Document::from_str("missing/1.md", "# One\n").unwrap();
+      let have = doc.save(dir.path());
+      assert!(
+        matches!(have, Err(Issue::CannotWriteFile { file, message: _ }) if file == "missing/1.md")
+      );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn through_symlink() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("shared/1.md", "# One\n\nold\n", dir.path());
+      test::create_symlink("1.md", "shared/1.md", dir.path());
+      let doc = Document::from_str("1.md", "# One\n\nnew\n").unwrap();
+      doc.save(dir.path()).unwrap();
+      assert!(
+        fs::symlink_metadata(dir.path().join("1.md"))
+          .unwrap()
+          .is_symlink()
+      );
+      assert_eq!(
+        test::load_file("shared/1.md", dir.path()),
+        S("# One\n\nnew\n")
+      );
+    }
+  }
+
+  mod line_mut {
+    use super::super::Document;
+    use crate::database::Line;
+    use indoc::indoc;
+
+    #[test]
+    fn lines() {
+      let give = indoc! {"
+                # Title
+                title text
+                ### Section
+                section text
+                "};
+      let mut doc = Document::from_str("test.md", give).unwrap();
+      pretty::assert_eq!(doc.line_mut(0), Some(&mut Line::from("# Title")));
+      pretty::assert_eq!(doc.line_mut(1), Some(&mut Line::from("title text")));
+      pretty::assert_eq!(doc.line_mut(2), Some(&mut Line::from("### Section")));
+      pretty::assert_eq!(doc.line_mut(3), Some(&mut Line::from("section text")));
+      pretty::assert_eq!(doc.line_mut(4), None);
+    }
+  }
+
   mod lines_count {
     use super::super::Document;
     use indoc::indoc;
@@ -824,6 +1436,46 @@ mod tests {
     }
   }
 
+  mod refresh {
+    use super::super::Document;
+    use crate::database::{Line, Link};
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn after_removing_a_section() {
+      let give = indoc! {"
+                # Title
+                ### Section 1
+                one
+                ### Section 2
+                [two](2.md)
+                "};
+      let mut doc = Document::from_str("test.md", give).unwrap();
+      doc.content_sections.remove(0);
+      doc.content_sections[0]
+        .body
+        .push(Line::from("[three](3.md)"));
+      doc.refresh();
+      assert_eq!(doc.content_sections[0].line_number, 1);
+      let want = vec![
+        Link {
+          target: S("2.md"),
+          line: 2,
+          start: 0,
+          end: 11,
+        },
+        Link {
+          target: S("3.md"),
+          line: 3,
+          start: 0,
+          end: 13,
+        },
+      ];
+      pretty::assert_eq!(doc.links, want);
+    }
+  }
+
   #[test]
   fn references() {
     let text = indoc! {"
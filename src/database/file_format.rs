@@ -0,0 +1,146 @@
+use super::windows1252;
+
+/// how a file stores its text on disk
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FileFormat {
+  /// whether lines end with "\r\n" instead of "\n"
+  pub crlf: bool,
+  /// whether the file starts with a byte order mark
+  pub bom: bool,
+  /// whether the last line ends with a line break
+  pub final_newline: bool,
+  /// whether the file is encoded in Windows-1252 instead of UTF-8
+  pub windows1252: bool,
+}
+
+impl Default for FileFormat {
+  fn default() -> Self {
+    Self {
+      crlf: false,
+      bom: false,
+      final_newline: true,
+      windows1252: false,
+    }
+  }
+}
+
+const BOM: char = '\u{feff}';
+
+impl FileFormat {
+  /// provides the format of the given file content and the content without byte order mark
+  pub fn detect(content: &str) -> (Self, &str) {
+    let (bom, text) = match content.strip_prefix(BOM) {
+      Some(text) => (true, text),
+      None => (false, content),
+    };
+    let line_breaks = text.matches('\n').count();
+    let crlf_line_breaks = text.matches("\r\n").count();
+    let format = Self {
+      crlf: crlf_line_breaks * 2 > line_breaks,
+      bom,
+      final_newline: text.is_empty() || text.ends_with('\n'),
+      windows1252: false,
+    };
+    (format, text)
+  }
+
+  /// provides the given text, whose lines end with "\n", in this format
+  pub fn apply(&self, text: &str) -> String {
+    let text = if self.final_newline {
+      text
+    } else {
+      text.strip_suffix('\n').unwrap_or(text)
+    };
+    let mut result = String::with_capacity(text.len() + 3);
+    if self.bom {
+      result.push(BOM);
+    }
+    if self.crlf {
+      result.push_str(&text.replace('\n', "\r\n"));
+    } else {
+      result.push_str(text);
+    }
+    result
+  }
+
+  /// provides the bytes to store the given text, whose lines end with "\n", in this format,
+  /// or the first character that the encoding of this format cannot represent
+  pub fn encode(&self, text: &str) -> Result<Vec<u8>, char> {
+    let text = self.apply(text);
+    if self.windows1252 {
+      windows1252::encode(&text)
+    } else {
+      Ok(text.into_bytes())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod detect {
+    use super::super::FileFormat;
+
+    #[test]
+    fn unix() {
+      let have = FileFormat::detect("# One\n\ntext\n");
+      let want = (FileFormat::default(), "# One\n\ntext\n");
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn windows_with_bom() {
+      let have = FileFormat::detect("\u{feff}# One\r\n\r\ntext\r\n");
+      let want = (
+        FileFormat {
+          crlf: true,
+          bom: true,
+          final_newline: true,
+          windows1252: false,
+        },
+        "# One\r\n\r\ntext\r\n",
+      );
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn no_final_newline() {
+      let (have, _text) = FileFormat::detect("# One\n\ntext");
+      let want = FileFormat {
+        crlf: false,
+        bom: false,
+        final_newline: false,
+        windows1252: false,
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn mostly_crlf() {
+      let (have, _text) = FileFormat::detect("# One\r\n\r\ntext\n");
+      assert!(have.crlf);
+    }
+  }
+
+  mod apply {
+    use super::super::FileFormat;
+
+    #[test]
+    fn default() {
+      let have = FileFormat::default().apply("# One\n\ntext\n");
+      assert_eq!(have, "# One\n\ntext\n");
+    }
+
+    #[test]
+    fn windows_with_bom_without_final_newline() {
+      let format = FileFormat {
+        crlf: true,
+        bom: true,
+        final_newline: false,
+        windows1252: false,
+      };
+      let have = format.apply("# One\n\ntext\n");
+      assert_eq!(have, "\u{feff}# One\r\n\r\ntext");
+    }
+  }
+}
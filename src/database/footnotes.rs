@@ -36,6 +36,60 @@ impl Footnotes {
       .iter()
       .filter(|definition| !self.contains_reference(&definition.identifier))
   }
+
+  /// provides the definitions in the order in which they should appear in the document:
+  /// in the order of their first reference, unreferenced definitions last
+  pub fn definitions_in_reference_order(&self) -> Vec<&Footnote> {
+    let mut result: Vec<&Footnote> = self.definitions.iter().collect();
+    result.sort_by_key(|definition| {
+      self
+        .references
+        .iter()
+        .position(|reference| reference.identifier == definition.identifier)
+        .unwrap_or(usize::MAX)
+    });
+    result
+  }
+
+  /// provides the old and new identifiers of the numeric footnotes
+  /// so that they are numbered in the order of their first reference,
+  /// None if they are already numbered this way
+  /// or if renumbering isn't safe because numeric footnotes are missing or unused
+  pub fn renumbering(&self) -> Option<Vec<(String, String)>> {
+    let mut order: Vec<&str> = vec![];
+    for reference in &self.references {
+      if is_numeric(&reference.identifier) && !order.contains(&reference.identifier.as_str()) {
+        order.push(&reference.identifier);
+      }
+    }
+    if order
+      .iter()
+      .any(|identifier| !self.contains_definition(identifier))
+    {
+      return None;
+    }
+    if self.definitions.iter().any(|definition| {
+      is_numeric(&definition.identifier) && !self.contains_reference(&definition.identifier)
+    }) {
+      return None;
+    }
+    let renumbering: Vec<(String, String)> = order
+      .into_iter()
+      .enumerate()
+      .map(|(i, identifier)| (identifier.into(), (i + 1).to_string()))
+      .filter(|(old, new)| old != new)
+      .collect();
+    if renumbering.is_empty() {
+      None
+    } else {
+      Some(renumbering)
+    }
+  }
+}
+
+/// indicates whether the given footnote identifier is a number
+fn is_numeric(identifier: &str) -> bool {
+  identifier.chars().all(|c| c.is_ascii_digit())
 }
 
 /// reference to a footnote
@@ -112,6 +166,97 @@ mod tests {
     }
   }
 
+  mod definitions_in_reference_order {
+    use crate::database::{Footnote, Footnotes};
+    use big_s::S;
+
+    fn footnote(identifier: &str, line: u32) -> Footnote {
+      Footnote {
+        identifier: identifier.into(),
+        line,
+        ..Footnote::default()
+      }
+    }
+
+    #[test]
+    fn reordered() {
+      let give = Footnotes {
+        definitions: vec![footnote("unused", 5), footnote("b", 6), footnote("a", 7)],
+        references: vec![footnote("a", 1), footnote("b", 2), footnote("a", 3)],
+      };
+      let have: Vec<&String> = give
+        .definitions_in_reference_order()
+        .into_iter()
+        .map(|definition| &definition.identifier)
+        .collect();
+      assert_eq!(have, vec![&S("a"), &S("b"), &S("unused")]);
+    }
+  }
+
+  mod renumbering {
+    use crate::database::{Footnote, Footnotes};
+    use big_s::S;
+
+    fn footnote(identifier: &str) -> Footnote {
+      Footnote {
+        identifier: identifier.into(),
+        ..Footnote::default()
+      }
+    }
+
+    #[test]
+    fn out_of_order() {
+      let give = Footnotes {
+        definitions: vec![footnote("1"), footnote("2"), footnote("named")],
+        references: vec![
+          footnote("2"),
+          footnote("named"),
+          footnote("1"),
+          footnote("2"),
+        ],
+      };
+      let want = Some(vec![(S("2"), S("1")), (S("1"), S("2"))]);
+      assert_eq!(give.renumbering(), want);
+    }
+
+    #[test]
+    fn gap() {
+      let give = Footnotes {
+        definitions: vec![footnote("1"), footnote("3")],
+        references: vec![footnote("1"), footnote("3")],
+      };
+      let want = Some(vec![(S("3"), S("2"))]);
+      assert_eq!(give.renumbering(), want);
+    }
+
+    #[test]
+    fn in_order() {
+      let give = Footnotes {
+        definitions: vec![footnote("1"), footnote("2")],
+        references: vec![footnote("1"), footnote("2"), footnote("1")],
+      };
+      assert_eq!(give.renumbering(), None);
+    }
+
+    #[test]
+    fn missing_definition() {
+      let give = Footnotes {
+        definitions: vec![footnote("2")],
+        references: vec![footnote("2"), footnote("1")],
+      };
+      assert_eq!(give.renumbering(), None);
+    }
+
+    #[test]
+    fn unused_definition() {
+      let give = Footnotes {
+        definitions: vec![footnote("1"), footnote("2")],
+        references: vec![footnote("2")],
+      };
+      assert_eq!(give.renumbering(), None);
+    }
+  }
+
   mod missing_references {
     use crate::database::{Footnote, Footnotes};
     use big_s::S;
@@ -0,0 +1,495 @@
+use super::{Directory, Document};
+use ahash::{AHashMap, AHashSet};
+use std::collections::BTreeMap;
+
+/// inverted index over the words in all documents of a Tikibase
+pub struct SearchIndex<'a> {
+  /// the indexed documents
+  docs: Vec<&'a Document>,
+  /// lowercased word --> all its occurrences
+  words: BTreeMap<String, Vec<Posting<'a>>>,
+}
+
+impl<'a> SearchIndex<'a> {
+  /// provides the search index for the given directory and all its subdirectories
+  pub fn from_dir(dir: &'a Directory) -> Self {
+    let mut result = Self {
+      docs: vec![],
+      words: BTreeMap::new(),
+    };
+    result.add_dir(dir);
+    result
+  }
+
+  /// provides the documents matching all terms of the given query, most relevant first
+  pub fn search(&self, query: &Query) -> Vec<Hit> {
+    if query.terms.is_empty() {
+      return vec![];
+    }
+    // doc index --> occurrences of each query term in that document
+    let mut matches: AHashMap<usize, Vec<Vec<&Posting>>> = AHashMap::new();
+    // term index --> how many documents contain this term
+    let mut doc_frequencies = vec![0; query.terms.len()];
+    for (term_index, term) in query.terms.iter().enumerate() {
+      let mut docs_with_term = AHashSet::new();
+      for posting in self.occurrences(term) {
+        docs_with_term.insert(posting.doc);
+        if query
+          .section
+          .as_ref()
+          .is_some_and(|section| &posting.section.to_lowercase() != section)
+        {
+          continue;
+        }
+        let doc_matches = matches
+          .entry(posting.doc)
+          .or_insert_with(|| vec![vec![]; query.terms.len()]);
+        doc_matches[term_index].push(posting);
+      }
+      doc_frequencies[term_index] = docs_with_term.len();
+    }
+    matches.retain(|_doc, terms| terms.iter().all(|occurrences| !occurrences.is_empty()));
+    let mut result: Vec<Hit> = matches
+      .into_iter()
+      .map(|(doc_index, terms)| {
+        let doc = self.docs[doc_index];
+        let score = score(&terms, &doc_frequencies, self.docs.len());
+        let first = terms
+          .iter()
+          .flatten()
+          .min_by_key(|posting| (posting.line, posting.start))
+          .unwrap();
+        let text = doc
+          .lines()
+          .nth(first.line as usize)
+          .map(|line| line.text.trim().to_owned())
+          .unwrap_or_default();
+        Hit {
+          file: doc.relative_path.clone(),
+          line: first.line,
+          start: first.start,
+          end: first.end,
+          text,
+          score,
+        }
+      })
+      .collect();
+    result.sort_by(|a, b| {
+      b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.file.cmp(&b.file))
+    });
+    result
+  }
+
+  fn add_dir(&mut self, dir: &'a Directory) {
+    let mut paths: Vec<&String> = dir.docs.keys().collect();
+    paths.sort();
+    for path in paths {
+      self.add_doc(dir.docs.get(path).unwrap());
+    }
+    for subdir in dir.dirs.values() {
+      self.add_dir(subdir);
+    }
+  }
+
+  fn add_doc(&mut self, doc: &'a Document) {
+    let doc_index = self.docs.len();
+    self.docs.push(doc);
+    let mut sections = doc.sections().peekable();
+    let mut section = sections.next().unwrap();
+    for (line_number, line) in doc.lines().enumerate() {
+      let line_number = line_number as u32;
+      while let Some(next) = sections.next_if(|next| next.line_number <= line_number) {
+        section = next;
+      }
+      for (position, word) in tokenize(&line.text).into_iter().enumerate() {
+        self.words.entry(word.text).or_default().push(Posting {
+          doc: doc_index,
+          section: section.human_title(),
+          line: line_number,
+          position: position as u32,
+          start: word.start,
+          end: word.end,
+        });
+      }
+    }
+  }
+
+  /// provides all occurrences of the given term, for phrases the occurrences of their first word
+  fn occurrences(&self, term: &Term) -> Vec<&Posting<'a>> {
+    match term {
+      Term::Word(word) => self.words.get(word).into_iter().flatten().collect(),
+      Term::Prefix(prefix) => self
+        .words
+        .range(prefix.clone()..)
+        .take_while(|(word, _postings)| word.starts_with(prefix.as_str()))
+        .flat_map(|(_word, postings)| postings)
+        .collect(),
+      Term::Phrase(words) => {
+        let Some((first, rest)) = words.split_first() else {
+          return vec![];
+        };
+        let followers: Vec<AHashSet<(usize, u32, u32)>> = rest
+          .iter()
+          .map(|word| {
+            self
+              .words
+              .get(word)
+              .into_iter()
+              .flatten()
+              .map(|posting| (posting.doc, posting.line, posting.position))
+              .collect()
+          })
+          .collect();
+        self
+          .words
+          .get(first)
+          .into_iter()
+          .flatten()
+          .filter(|posting| {
+            followers.iter().enumerate().all(|(offset, positions)| {
+              positions.contains(&(
+                posting.doc,
+                posting.line,
+                posting.position + offset as u32 + 1,
+              ))
+            })
+          })
+          .collect()
+      }
+    }
+  }
+}
+
+/// relevance of a document containing the given occurrences of all query terms:
+/// the occurrence count of each term weighted by how rare the term is across documents,
+/// occurrences in the document title count triple
+fn score(terms: &[Vec<&Posting>], doc_frequencies: &[usize], docs_count: usize) -> f64 {
+  terms
+    .iter()
+    .zip(doc_frequencies)
+    .map(|(occurrences, doc_frequency)| {
+      let weight: u32 = occurrences
+        .iter()
+        .map(|posting| if posting.line == 0 { 3 } else { 1 })
+        .sum();
+      let rarity = (1.0 + docs_count as f64 / *doc_frequency as f64).ln();
+      f64::from(weight) * rarity
+    })
+    .sum()
+}
+
+/// an occurrence of a word in a document
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Posting<'a> {
+  /// index of the document in the search index
+  doc: usize,
+  /// human-readable title of the section containing the word
+  section: &'a str,
+  /// the line on which the word occurs
+  line: u32,
+  /// the how-many-th word on the line this is
+  position: u32,
+  /// where on the line the word starts
+  start: u32,
+  /// where on the line the word ends
+  end: u32,
+}
+
+/// a document matching a search query
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit {
+  /// relative path of the matching document
+  pub file: String,
+  /// the first line containing a match
+  pub line: u32,
+  /// where on the line the match starts
+  pub start: u32,
+  /// where on the line the match ends
+  pub end: u32,
+  /// content of the matching line
+  pub text: String,
+  /// relevance of this document for the query
+  pub score: f64,
+}
+
+/// a parsed search query
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Query {
+  /// all of these terms must occur in a matching document
+  terms: Vec<Term>,
+  /// if set, the terms must occur in a section with this lowercased title
+  section: Option<String>,
+}
+
+impl Query {
+  /// parses the given query text:
+  /// `"exact phrase"`, `prefix*`, `section:title` (or `section:"long title"`), and plain words
+  pub fn parse(text: &str) -> Self {
+    let mut result = Self::default();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+      if let Some(section) = rest.strip_prefix("section:") {
+        let (value, remainder) = next_value(section);
+        if !value.is_empty() {
+          result.section = Some(value.to_lowercase());
+        }
+        rest = remainder;
+      } else if rest.starts_with('"') {
+        let (value, remainder) = next_value(rest);
+        result.add_words(tokenize(value).into_iter().map(|word| word.text).collect());
+        rest = remainder;
+      } else {
+        let (value, remainder) = next_value(rest);
+        match value.strip_suffix('*') {
+          Some(prefix) if !prefix.is_empty() => {
+            result.terms.push(Term::Prefix(prefix.to_lowercase()));
+          }
+          _ => result.add_words(tokenize(value).into_iter().map(|word| word.text).collect()),
+        }
+        rest = remainder;
+      }
+      rest = rest.trim_start();
+    }
+    result
+  }
+
+  fn add_words(&mut self, mut words: Vec<String>) {
+    match words.len() {
+      0 => {}
+      1 => self.terms.push(Term::Word(words.pop().unwrap())),
+      _ => self.terms.push(Term::Phrase(words)),
+    }
+  }
+}
+
+/// a part of a search query
+#[derive(Debug, Eq, PartialEq)]
+enum Term {
+  Word(String),
+  Prefix(String),
+  Phrase(Vec<String>),
+}
+
+/// provides the first value in the given query text, either quoted or until the next whitespace,
+/// and the remaining query text
+fn next_value(text: &str) -> (&str, &str) {
+  if let Some(quoted) = text.strip_prefix('"') {
+    return match quoted.split_once('"') {
+      Some((value, rest)) => (value, rest),
+      None => (quoted, ""),
+    };
+  }
+  match text.split_once(char::is_whitespace) {
+    Some((value, rest)) => (value, rest),
+    None => (text, ""),
+  }
+}
+
+/// a word in a line of text
+#[derive(Debug, Eq, PartialEq)]
+struct Word {
+  /// the lowercased word
+  text: String,
+  start: u32,
+  end: u32,
+}
+
+/// provides the words in the given text
+fn tokenize(text: &str) -> Vec<Word> {
+  let mut result = vec![];
+  let mut start: Option<usize> = None;
+  for (i, c) in text.char_indices() {
+    match (c.is_alphanumeric(), start) {
+      (true, None) => start = Some(i),
+      (false, Some(word_start)) => {
+        result.push(Word {
+          text: text[word_start..i].to_lowercase(),
+          start: word_start as u32,
+          end: i as u32,
+        });
+        start = None;
+      }
+      _ => {}
+    }
+  }
+  if let Some(word_start) = start {
+    result.push(Word {
+      text: text[word_start..].to_lowercase(),
+      start: word_start as u32,
+      end: text.len() as u32,
+    });
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod search {
+    use crate::database::{Query, SearchIndex, Tikibase};
+    use crate::test;
+    use indoc::indoc;
+
+    fn search(files: &[(&str, &str)], query: &str) -> Vec<(String, u32)> {
+      let dir = camino_tempfile::tempdir().unwrap();
+      for (name, content) in files {
+        test::create_file(name, content, dir.path());
+      }
+      let base = Tikibase::load(dir.path()).unwrap();
+      let index = SearchIndex::from_dir(&base.dir);
+      index
+        .search(&Query::parse(query))
+        .into_iter()
+        .map(|hit| (hit.file, hit.line))
+        .collect()
+    }
+
+    #[test]
+    fn all_words_must_match() {
+      let files = [("1.md", "# One\nRust and Go"), ("2.md", "# Two\nonly rust")];
+      let have = search(&files, "rust go");
+      assert_eq!(have, vec![("1.md".into(), 1)]);
+    }
+
+    #[test]
+    fn ranks_by_relevance() {
+      let files = [
+        ("1.md", "# One\nrust"),
+        ("2.md", "# Rust\nrust and more rust"),
+        ("3.md", "# Three\nrust rust"),
+      ];
+      let have = search(&files, "rust");
+      let want = vec![("2.md".into(), 0), ("3.md".into(), 1), ("1.md".into(), 1)];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn rare_terms_weigh_more() {
+      let files = [
+        ("1.md", "# One\nrust rust go"),
+        ("2.md", "# Two\nrust go go"),
+        ("3.md", "# Three\nrust"),
+        ("4.md", "# Four\nrust"),
+      ];
+      let have = search(&files, "rust go");
+      let want = vec![("2.md".into(), 1), ("1.md".into(), 1)];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn phrase() {
+      let files = [
+        ("1.md", "# One\nthe borrow checker"),
+        ("2.md", "# Two\nthe checker of borrow"),
+      ];
+      let have = search(&files, r#""borrow checker""#);
+      assert_eq!(have, vec![("1.md".into(), 1)]);
+    }
+
+    #[test]
+    fn prefix() {
+      let files = [
+        ("1.md", "# One\nborrowing"),
+        ("2.md", "# Two\nborrow"),
+        ("3.md", "# Three\nburrow"),
+      ];
+      let have = search(&files, "borrow*");
+      assert_eq!(have, vec![("1.md".into(), 1), ("2.md".into(), 1)]);
+    }
+
+    #[test]
+    fn section_scoped() {
+      let one = indoc! {"
+        # One
+        rust
+
+        ### summary
+        go
+        "};
+      let two = indoc! {"
+        # Two
+        go
+
+        ### Summary
+        rust
+        "};
+      let files = [("1.md", one), ("2.md", two)];
+      let have = search(&files, "section:summary rust");
+      assert_eq!(have, vec![("2.md".into(), 4)]);
+    }
+
+    #[test]
+    fn no_terms() {
+      let files = [("1.md", "# One\nrust")];
+      let have = search(&files, "section:summary");
+      assert_eq!(have, vec![]);
+    }
+  }
+
+  mod query_parse {
+    use super::super::{Query, Term};
+    use big_s::S;
+
+    #[test]
+    fn all_term_types() {
+      let have = Query::parse(r#"Rust "borrow checker" lif* section:"Key Points""#);
+      let want = Query {
+        terms: vec![
+          Term::Word(S("rust")),
+          Term::Phrase(vec![S("borrow"), S("checker")]),
+          Term::Prefix(S("lif")),
+        ],
+        section: Some(S("key points")),
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unterminated_phrase() {
+      let have = Query::parse(r#"one "two three"#);
+      let want = Query {
+        terms: vec![
+          Term::Word(S("one")),
+          Term::Phrase(vec![S("two"), S("three")]),
+        ],
+        section: None,
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn empty() {
+      assert_eq!(Query::parse("  "), Query::default());
+    }
+  }
+
+  mod tokenize {
+    use super::super::{Word, tokenize};
+    use big_s::S;
+
+    #[test]
+    fn punctuation_and_case() {
+      let have = tokenize("Hello, wörld-42!");
+      let want = vec![
+        Word {
+          text: S("hello"),
+          start: 0,
+          end: 5,
+        },
+        Word {
+          text: S("wörld"),
+          start: 7,
+          end: 13,
+        },
+        Word {
+          text: S("42"),
+          start: 14,
+          end: 16,
+        },
+      ];
+      assert_eq!(have, want);
+    }
+  }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Eq, Hash, PartialEq)]
+use crate::fspath;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Image {
   pub src: String,
   pub line: u32,
@@ -7,8 +9,9 @@ pub struct Image {
 }
 
 impl Image {
+  /// indicates whether this image points to the file with the given path, ignoring percent-encoding
   pub fn points_to(&self, path: &str) -> bool {
-    self.src == path
+    self.src == path || fspath::decode(&self.src) == path
   }
 }
 
@@ -40,5 +43,16 @@ mod tests {
       };
       assert!(!img.points_to("other.md"));
     }
+
+    #[test]
+    fn percent_encoded() {
+      let img = Image {
+        src: S("my%20photo.png"),
+        line: 0,
+        start: 0,
+        end: 0,
+      };
+      assert!(img.points_to("my photo.png"));
+    }
   }
 }
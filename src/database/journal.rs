@@ -0,0 +1,178 @@
+use super::document::replace_file;
+use super::tikibase::copy_file;
+use crate::check::Issue;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+
+/// the directory inside the Tikibase root that stores the journal of the last fix
+pub const JOURNAL_DIR: &str = ".tikibase/journal";
+
+/// the directory in which the journal of a running fix gets recorded
+const NEW_JOURNAL_DIR: &str = ".tikibase/journal.new";
+
+/// the file inside the journal directory that lists the changed files
+const FILE_LIST: &str = "files.json";
+
+/// records the content of all files that a fix changes,
+/// so that the fix can be undone later
+pub struct Journal {
+  /// the directory in which this journal gets recorded
+  dir: Utf8PathBuf,
+  /// relative paths of the files recorded so far
+  files: Vec<String>,
+}
+
+impl Journal {
+  /// begins recording a new journal for the Tikibase at the given root
+  pub fn start(root: &Utf8Path) -> Self {
+    let dir = root.join(NEW_JOURNAL_DIR);
+    // remnants of an earlier run that crashed
+    let _ = fs::remove_dir_all(&dir);
+    Self { dir, files: vec![] }
+  }
+
+  /// stores the current content of the given file, unless it was already recorded
+  pub fn record(&mut self, root: &Utf8Path, relative_path: &str) -> Result<(), Issue> {
+    if self.files.iter().any(|file| file == relative_path) {
+      return Ok(());
+    }
+    copy_file(
+      &root.join(relative_path),
+      &self.dir.join("before").join(relative_path),
+    )?;
+    self.files.push(relative_path.into());
+    Ok(())
+  }
+
+  /// stores the fixed content of all recorded files
+  /// and replaces the journal of the previous fix with this one
+  pub fn finish(self, root: &Utf8Path) -> Result<(), Issue> {
+    if self.files.is_empty() {
+      return Ok(());
+    }
+    for file in &self.files {
+      copy_file(&root.join(file), &self.dir.join("after").join(file))?;
+    }
+    let file_list = self.dir.join(FILE_LIST);
+    let json = serde_json::to_string_pretty(&self.files).unwrap_or_default();
+    fs::write(&file_list, json).map_err(|err| Issue::CannotWriteFile {
+      file: file_list.to_string(),
+      message: err.to_string(),
+    })?;
+    let journal_dir = root.join(JOURNAL_DIR);
+    let result = if journal_dir.exists() {
+      fs::remove_dir_all(&journal_dir)
+    } else {
+      Ok(())
+    }
+    .and_then(|()| fs::rename(&self.dir, &journal_dir));
+    result.map_err(|err| Issue::CannotWriteFile {
+      file: JOURNAL_DIR.into(),
+      message: err.to_string(),
+    })
+  }
+}
+
+/// restores the content of all files before the last fix,
+/// provides the relative paths of the restored files
+pub fn undo(root: &Utf8Path) -> Result<Vec<String>, Vec<Issue>> {
+  let journal_dir = root.join(JOURNAL_DIR);
+  let Ok(json) = fs::read_to_string(journal_dir.join(FILE_LIST)) else {
+    return Err(vec![Issue::NothingToUndo]);
+  };
+  let files: Vec<String> = match serde_json::from_str(&json) {
+    Ok(files) => files,
+    Err(err) => {
+      return Err(vec![Issue::CannotReadFile {
+        file: format!("{JOURNAL_DIR}/{FILE_LIST}"),
+        message: err.to_string(),
+      }]);
+    }
+  };
+  let mut issues = vec![];
+  for file in &files {
+    let current = fs::read(root.join(file)).ok();
+    let fixed = fs::read(journal_dir.join("after").join(file)).ok();
+    if current.is_none() || current != fixed {
+      issues.push(Issue::FileChangedSinceFix { file: file.clone() });
+    }
+  }
+  if !issues.is_empty() {
+    return Err(issues);
+  }
+  for file in &files {
+    let original = journal_dir.join("before").join(file);
+    let result = fs::read(&original).and_then(|content| replace_file(&root.join(file), &content));
+    if let Err(err) = result {
+      issues.push(Issue::CannotWriteFile {
+        file: file.clone(),
+        message: err.to_string(),
+      });
+    }
+  }
+  if !issues.is_empty() {
+    return Err(issues);
+  }
+  if let Err(err) = fs::remove_dir_all(&journal_dir) {
+    return Err(vec![Issue::CannotWriteFile {
+      file: JOURNAL_DIR.into(),
+      message: err.to_string(),
+    }]);
+  }
+  Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod undo {
+    use super::super::{JOURNAL_DIR, Journal, undo};
+    use crate::check::Issue;
+    use crate::test;
+    use big_s::S;
+
+    #[test]
+    fn restores_original_content() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\noriginal\n", dir.path());
+      let mut journal = Journal::start(dir.path());
+      journal.record(dir.path(), "1.md").unwrap();
+      test::create_file("1.md", "# One\n\nfixed\n", dir.path());
+      journal.finish(dir.path()).unwrap();
+      let have = undo(dir.path()).unwrap();
+      assert_eq!(have, vec![S("1.md")]);
+      assert_eq!(test::load_file("1.md", dir.path()), "# One\n\noriginal\n");
+      assert!(!dir.path().join(".1.md.tikibase-tmp").exists());
+      assert!(!dir.path().join(JOURNAL_DIR).exists());
+    }
+
+    #[test]
+    fn changed_since_fix() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\noriginal\n", dir.path());
+      let mut journal = Journal::start(dir.path());
+      journal.record(dir.path(), "1.md").unwrap();
+      test::create_file("1.md", "# One\n\nfixed\n", dir.path());
+      journal.finish(dir.path()).unwrap();
+      test::create_file("1.md", "# One\n\nedited\n", dir.path());
+      let have = undo(dir.path()).unwrap_err();
+      let want = vec![Issue::FileChangedSinceFix { file: S("1.md") }];
+      assert_eq!(have, want);
+      assert_eq!(test::load_file("1.md", dir.path()), "# One\n\nedited\n");
+    }
+
+    #[test]
+    fn no_journal() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let have = undo(dir.path()).unwrap_err();
+      assert_eq!(have, vec![Issue::NothingToUndo]);
+    }
+
+    #[test]
+    fn nothing_recorded() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      Journal::start(dir.path()).finish(dir.path()).unwrap();
+      assert!(!dir.path().join(JOURNAL_DIR).exists());
+    }
+  }
+}
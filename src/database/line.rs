@@ -1,19 +1,14 @@
 use crate::check::{Issue, Location};
-use crate::database::{Footnote, Footnotes, Image, Link};
+use crate::database::{Footnote, Footnotes};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-#[derive(Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Line {
   pub text: String,
 }
 
-static MD_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(!?)\[[^\]]*\]\(([^)]*)\)").unwrap());
-static A_HTML_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<a href="(.*)">(.*)</a>"#).unwrap());
-static IMG_HTML_REGEX: Lazy<Regex> =
-  Lazy::new(|| Regex::new(r#"<img src="([^"]*)"[^>]*>"#).unwrap());
 static FOOTNOTE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[\^([\w-]+)\](:?)").unwrap());
-static CODEBLOCK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*```").unwrap());
 
 impl Line {
   /// appends all footnote definitions and references to the given result structure
@@ -43,53 +38,6 @@ impl Line {
     }
     Ok(())
   }
-
-  /// indicates whether this line is the beginning or end of a code block
-  pub fn is_code_block_boundary(&self) -> bool {
-    CODEBLOCK_REGEX.is_match(&self.text)
-  }
-
-  /// populates the given accumulator with all links and images in this line
-  pub fn references(&self, line: u32, links: &mut Vec<Link>, images: &mut Vec<Image>) {
-    for cap in MD_REGEX.captures_iter(&self.text) {
-      let full_match = cap.get(0).unwrap();
-      match &cap[1] {
-        "!" => images.push(Image {
-          src: cap[2].to_string(),
-          line,
-          start: full_match.start() as u32,
-          end: full_match.end() as u32,
-        }),
-        "" => {
-          links.push(Link {
-            target: cap[2].into(),
-            line,
-            start: full_match.start() as u32,
-            end: full_match.end() as u32,
-          });
-        }
-        _ => panic!("unexpected capture: '{}'", &cap[1]),
-      }
-    }
-    for cap in A_HTML_REGEX.captures_iter(&self.text) {
-      let full_match = cap.get(0).unwrap();
-      links.push(Link {
-        target: cap[1].into(),
-        line,
-        start: full_match.start() as u32,
-        end: full_match.end() as u32,
-      });
-    }
-    for cap in IMG_HTML_REGEX.captures_iter(&self.text) {
-      let full_match = cap.get(0).unwrap();
-      images.push(Image {
-        src: cap[1].to_string(),
-        line,
-        start: full_match.start() as u32,
-        end: full_match.end() as u32,
-      });
-    }
-  }
 }
 
 impl<IS: Into<String>> From<IS> for Line {
@@ -98,37 +46,54 @@ impl<IS: Into<String>> From<IS> for Line {
   }
 }
 
-/// non-destructively overwrites areas inside backticks in the given string with spaces
+/// non-destructively overwrites the content of code spans in the given string with spaces,
+/// a code span starts with a run of backticks and ends with the next run of the same length
 fn sanitize_code_segments(text: &str, file: &str, line: u32) -> Result<String, Issue> {
   let mut result = String::with_capacity(text.len());
-  let mut code_block_start: Option<u32> = None;
-  for (i, c) in text.char_indices() {
-    if c == '`' {
-      code_block_start = match code_block_start {
-        Some(_) => None,
-        None => Some(i as u32),
-      };
-      result.push(c);
-      continue;
-    }
-    result.push(match code_block_start {
-      Some(_) => ' ',
-      None => c,
-    });
-  }
-  if let Some(code_block_start) = code_block_start {
-    return Err(Issue::UnclosedBacktick {
-      location: Location {
-        file: file.into(),
-        line,
-        start: code_block_start,
-        end: text.len() as u32,
-      },
-    });
+  let mut pos = 0;
+  while let Some(found) = text[pos..].find('`') {
+    let start = pos + found;
+    let len = backtick_run_len(&text[start..]);
+    let content_start = start + len;
+    let Some(content_end) = closing_backticks(text, content_start, len) else {
+      return Err(Issue::UnclosedBacktick {
+        location: Location {
+          file: file.into(),
+          line,
+          start: start as u32,
+          end: text.len() as u32,
+        },
+      });
+    };
+    result.push_str(&text[pos..content_start]);
+    result.push_str(&" ".repeat(content_end - content_start));
+    result.push_str(&text[content_end..content_end + len]);
+    pos = content_end + len;
   }
+  result.push_str(&text[pos..]);
   Ok(result)
 }
 
+/// provides the position of the next run of exactly the given number of backticks in the given text,
+/// starting at the given position
+fn closing_backticks(text: &str, from: usize, len: usize) -> Option<usize> {
+  let mut pos = from;
+  while let Some(found) = text[pos..].find('`') {
+    let start = pos + found;
+    let run = backtick_run_len(&text[start..]);
+    if run == len {
+      return Some(start);
+    }
+    pos = start + run;
+  }
+  None
+}
+
+/// provides the number of backticks at the beginning of the given text
+fn backtick_run_len(text: &str) -> usize {
+  text.bytes().take_while(|byte| *byte == b'`').count()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -189,7 +154,7 @@ mod tests {
 
     #[test]
     fn ignore_code_looking_like_footnotes() {
-      let line = Line::from("the code `map[^0]`");
+      let line = Line::from("the code `map[^0]` and ``map[`^1`]``");
       let mut have = Footnotes::default();
       line.add_footnotes_to(&mut have, "", 0).unwrap();
       let want = Footnotes::default();
@@ -197,151 +162,6 @@ mod tests {
     }
   }
 
-  mod is_code_block_boundary {
-    use crate::database::Line;
-
-    #[test]
-    fn no_boundary() {
-      let line = Line::from("foo");
-      let have = line.is_code_block_boundary();
-      assert!(!have);
-    }
-
-    #[test]
-    fn plain_boundary() {
-      let line = Line::from("```");
-      let have = line.is_code_block_boundary();
-      assert!(have);
-    }
-
-    #[test]
-    fn boundary_with_language() {
-      let line = Line::from("```rs");
-      let have = line.is_code_block_boundary();
-      assert!(have);
-    }
-  }
-
-  mod references {
-    use super::super::{Image, Link};
-    use crate::database::Line;
-    use big_s::S;
-
-    #[test]
-    fn link_md() {
-      let line =
-        Line::from(r"an MD link: [one](one.md) and one to a section: [two pieces](two.md#pieces)!");
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![
-        Link {
-          target: S("one.md"),
-          line: 12,
-          start: 12,
-          end: 25,
-        },
-        Link {
-          target: S("two.md#pieces"),
-          line: 12,
-          start: 48,
-          end: 75,
-        },
-      ];
-      pretty::assert_eq!(links, want);
-    }
-
-    #[test]
-    fn link_html() {
-      let line = Line::from(r#"an HTML link: <a href="two.md">two</a>"#);
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Link {
-        target: S("two.md"),
-        line: 12,
-        start: 14,
-        end: 38,
-      }];
-      pretty::assert_eq!(links, want);
-    }
-
-    #[test]
-    fn img_md() {
-      let line = Line::from(r"an MD image: ![zonk](zonk.md)");
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Image {
-        src: S("zonk.md"),
-        line: 12,
-        start: 13,
-        end: 29,
-      }];
-      pretty::assert_eq!(images, want);
-    }
-
-    #[test]
-    fn img_html() {
-      let line = Line::from(r#"<img src="zonk.md">"#);
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Image {
-        src: S("zonk.md"),
-        line: 12,
-        start: 0,
-        end: 19,
-      }];
-      pretty::assert_eq!(images, want);
-    }
-
-    #[test]
-    fn img_html_extra_attributes() {
-      let line = Line::from(r#"<img src="zonk.md" width="10" height="10">"#);
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Image {
-        src: S("zonk.md"),
-        line: 12,
-        start: 0,
-        end: 42,
-      }];
-      pretty::assert_eq!(images, want);
-    }
-
-    #[test]
-    fn img_xml_nospace() {
-      let line = Line::from(r#"<img src="zonk.md"/>"#);
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Image {
-        src: S("zonk.md"),
-        line: 12,
-        start: 0,
-        end: 20,
-      }];
-      pretty::assert_eq!(images, want);
-    }
-
-    #[test]
-    fn img_xml_space() {
-      let line = Line::from(r#"<img src="zonk.md" />"#);
-      let mut images = vec![];
-      let mut links = vec![];
-      line.references(12, &mut links, &mut images);
-      let want = vec![Image {
-        src: S("zonk.md"),
-        line: 12,
-        start: 0,
-        end: 21,
-      }];
-      pretty::assert_eq!(images, want);
-    }
-  }
-
   mod sanitize_code_segments {
     use super::super::sanitize_code_segments;
     use crate::check::{Issue, Location};
@@ -354,6 +174,20 @@ mod tests {
       assert_eq!(sanitize_code_segments(give, "", 0), Ok(want));
     }
 
+    #[test]
+    fn multiple_backticks() {
+      let give = "one ``code with ` inside`` two ```more `` code``` three";
+      let want = S("one ``                  `` two ```            ``` three");
+      assert_eq!(sanitize_code_segments(give, "", 0), Ok(want));
+    }
+
+    #[test]
+    fn multibyte_characters() {
+      let give = "`ö` [^1]";
+      let want = S("`  ` [^1]");
+      assert_eq!(sanitize_code_segments(give, "", 0), Ok(want));
+    }
+
     #[test]
     fn empty_string() {
       let give = "";
@@ -375,5 +209,20 @@ mod tests {
       let have = sanitize_code_segments(give, "", 12);
       assert_eq!(have, want);
     }
+
+    #[test]
+    fn unclosed_multiple_backticks() {
+      let give = "one ``unclosed` code";
+      let want = Err(Issue::UnclosedBacktick {
+        location: Location {
+          file: S(""),
+          line: 12,
+          start: 4,
+          end: 20,
+        },
+      });
+      let have = sanitize_code_segments(give, "", 12);
+      assert_eq!(have, want);
+    }
   }
 }
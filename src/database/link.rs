@@ -1,4 +1,6 @@
-#[derive(Debug, Eq, Hash, PartialEq)]
+use crate::fspath;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Link {
   pub target: String,
   pub line: u32,
@@ -7,11 +9,14 @@ pub struct Link {
 }
 
 impl Link {
+  /// indicates whether this link points to the file with the given path,
+  /// ignoring anchors and percent-encoding
   pub fn points_to(&self, path: &str) -> bool {
-    match self.target.split_once('#') {
-      Some((base, _anchor)) => base == path,
-      None => self.target == path,
-    }
+    let target_file = match self.target.split_once('#') {
+      Some((base, _anchor)) => base,
+      None => &self.target,
+    };
+    target_file == path || fspath::decode(target_file) == path
   }
 }
 
@@ -54,5 +59,16 @@ mod tests {
       };
       assert!(img.points_to("ok.md"));
     }
+
+    #[test]
+    fn percent_encoded() {
+      let link = Link {
+        target: S("my%20note.md#foo"),
+        line: 0,
+        start: 0,
+        end: 0,
+      };
+      assert!(link.points_to("my note.md"));
+    }
   }
 }
@@ -0,0 +1,134 @@
+/// link reference definitions and the reference-style links using them in a document
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct LinkReferences {
+  pub definitions: Vec<LinkReference>,
+  pub references: Vec<LinkReference>,
+}
+
+impl LinkReferences {
+  /// indicates whether this collection contains a definition for the given label
+  fn contains_definition(&self, label: &str) -> bool {
+    self
+      .definitions
+      .iter()
+      .any(|definition| definition.matches(label))
+  }
+
+  /// indicates whether this collection contains a reference using the given label
+  fn contains_reference(&self, label: &str) -> bool {
+    self
+      .references
+      .iter()
+      .any(|reference| reference.matches(label))
+  }
+
+  /// provides the references that use labels without definition
+  pub fn missing_references(&self) -> impl Iterator<Item = &LinkReference> {
+    self
+      .references
+      .iter()
+      .filter(|reference| !self.contains_definition(&reference.label))
+  }
+
+  /// provides the definitions that no reference uses
+  pub fn unused_definitions(&self) -> impl Iterator<Item = &LinkReference> {
+    self
+      .definitions
+      .iter()
+      .filter(|definition| !self.contains_reference(&definition.label))
+  }
+}
+
+/// a link reference definition like `[label]: target` or a reference-style link like `[text][label]`
+#[derive(Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LinkReference {
+  /// the label as written in the document
+  pub label: String,
+  /// the line on which this definition or reference exists
+  pub line: u32,
+  /// where on the line it starts
+  pub start: u32,
+  /// where on the line it ends
+  pub end: u32,
+}
+
+impl LinkReference {
+  /// indicates whether this definition or reference has the given label,
+  /// labels match case-insensitively and ignoring differences in whitespace
+  pub fn matches(&self, label: &str) -> bool {
+    normalize_label(&self.label) == normalize_label(label)
+  }
+}
+
+/// provides the given label in the form in which labels get compared
+fn normalize_label(label: &str) -> String {
+  label
+    .split_whitespace()
+    .collect::<Vec<&str>>()
+    .join(" ")
+    .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod missing_references {
+    use crate::database::{LinkReference, LinkReferences};
+    use big_s::S;
+
+    #[test]
+    fn missing() {
+      let give = LinkReferences {
+        definitions: vec![LinkReference {
+          label: S("One"),
+          ..LinkReference::default()
+        }],
+        references: vec![
+          LinkReference {
+            label: S("one"),
+            ..LinkReference::default()
+          },
+          LinkReference {
+            label: S("two"),
+            ..LinkReference::default()
+          },
+        ],
+      };
+      let have: Vec<&str> = give
+        .missing_references()
+        .map(|r| r.label.as_str())
+        .collect();
+      assert_eq!(have, vec!["two"]);
+    }
+  }
+
+  mod unused_definitions {
+    use crate::database::{LinkReference, LinkReferences};
+    use big_s::S;
+
+    #[test]
+    fn unused() {
+      let give = LinkReferences {
+        definitions: vec![
+          LinkReference {
+            label: S("the  One"),
+            ..LinkReference::default()
+          },
+          LinkReference {
+            label: S("two"),
+            ..LinkReference::default()
+          },
+        ],
+        references: vec![LinkReference {
+          label: S("The one"),
+          ..LinkReference::default()
+        }],
+      };
+      let have: Vec<&str> = give
+        .unused_definitions()
+        .map(|r| r.label.as_str())
+        .collect();
+      assert_eq!(have, vec!["two"]);
+    }
+  }
+}
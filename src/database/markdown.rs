@@ -0,0 +1,814 @@
+use super::{Image, Link, LinkReference, LinkReferences};
+use core::ops::Range;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{
+  BrokenLink, CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Options, Parser, Tag,
+};
+use regex::{Captures, Regex};
+
+/// HTML tags that reference other files: `<a href="...">`, `</a>`, and `<img src="...">`
+static HTML_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"<a\s[^>]*?href="([^"]*)"[^>]*>|</a\s*>|<img\s[^>]*?src="([^"]*)"[^>]*>"#).unwrap()
+});
+
+/// HTML tags and complete HTML comments
+static HTML_MARKUP_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<!--.*?-->|<[^>]*>").unwrap());
+
+/// HTML tags that define a link target: `<a id="...">`, `<a name="...">`, and other tags with an id
+static HTML_ANCHOR_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"<\w+\s[^>]*?\b(?:id|name)="([^"]*)""#).unwrap());
+
+/// the Markdown extensions that Tikibase documents can use
+pub fn options() -> Options {
+  Options::ENABLE_HEADING_ATTRIBUTES
+    | Options::ENABLE_TABLES
+    | Options::ENABLE_FOOTNOTES
+    | Options::ENABLE_STRIKETHROUGH
+    | Options::ENABLE_TASKLISTS
+}
+
+/// provides the plain text of the given heading title, without Markdown formatting
+pub fn heading_text(title: &str) -> String {
+  let mut result = String::new();
+  for event in Parser::new_ext(&format!("# {title}"), options()) {
+    if let Event::Text(text) | Event::Code(text) = event {
+      result.push_str(&text);
+    }
+  }
+  result
+}
+
+/// Markdown text made up of lines that know their line number in the document
+pub struct Markdown {
+  /// the lines joined with newlines
+  text: String,
+  /// byte offset in text at which each line starts
+  line_starts: Vec<usize>,
+  /// line number in the document of each line
+  line_numbers: Vec<u32>,
+}
+
+impl Markdown {
+  /// provides the Markdown consisting of the given lines, with their line numbers in the document
+  pub fn new<'a, I: IntoIterator<Item = (u32, &'a str)>>(lines: I) -> Self {
+    let mut text = String::new();
+    let mut line_starts = vec![];
+    let mut line_numbers = vec![];
+    for (line_number, line) in lines {
+      line_starts.push(text.len());
+      line_numbers.push(line_number);
+      text.push_str(line);
+      text.push('\n');
+    }
+    Self {
+      text,
+      line_starts,
+      line_numbers,
+    }
+  }
+
+  /// provides all fenced and indented code blocks in this Markdown text
+  pub fn code_blocks(&self) -> Vec<CodeBlock> {
+    let mut result = vec![];
+    for (event, range) in Parser::new_ext(&self.text, options()).into_offset_iter() {
+      let Event::Start(Tag::CodeBlock(kind)) = event else {
+        continue;
+      };
+      let (first, column) = self.position(range.start);
+      let (last, _) = self.position(range.end.saturating_sub(1).max(range.start));
+      let closed = match kind {
+        CodeBlockKind::Indented => true,
+        // fences can also end with the end of their container, only a fence running until the end of the text is unclosed
+        CodeBlockKind::Fenced(_) => {
+          let opening = &self.line_text(first)[column..];
+          range.end < self.text.len()
+            || (last > first && is_closing_fence(opening, self.line_text(last)))
+        }
+      };
+      result.push(CodeBlock {
+        start: self.line_numbers[first],
+        end: self.line_numbers[last],
+        closed,
+      });
+    }
+    result
+  }
+
+  /// provides the anchors, e.g. "#foo", that HTML tags in this Markdown text define via id or name attributes
+  pub fn html_anchors(&self) -> Vec<String> {
+    let mut result = vec![];
+    for (event, _range) in Parser::new_ext(&self.text, options()).into_offset_iter() {
+      if let Event::Html(html) | Event::InlineHtml(html) = event {
+        for captures in HTML_ANCHOR_REGEX.captures_iter(&html) {
+          result.push(format!("#{}", &captures[1]));
+        }
+      }
+    }
+    result
+  }
+
+  /// provides all headings that start a new section, i.e. that aren't nested inside lists or block quotes
+  pub fn headings(&self) -> Vec<Heading> {
+    let mut result = vec![];
+    for (event, range) in Parser::new_ext(&self.text, options()).into_offset_iter() {
+      if let Event::Start(Tag::Heading { level, .. }) = event {
+        let (index, column) = self.position(range.start);
+        if column > 0 {
+          continue;
+        }
+        let setext = !self.text[range.start..].starts_with('#');
+        result.push(Heading {
+          line: self.line_numbers[index],
+          level: heading_level(level),
+          setext,
+        });
+      }
+    }
+    result
+  }
+
+  /// provides the link reference definitions and the reference-style links and images in this Markdown text
+  pub fn link_references(&self) -> LinkReferences {
+    let mut result = LinkReferences::default();
+    let mut broken: Vec<BrokenLink> = vec![];
+    let callback = |link: BrokenLink<'_>| -> Option<(CowStr<'_>, CowStr<'_>)> {
+      broken.push(link.into_static());
+      None
+    };
+    let mut parser = Parser::new_with_broken_link_callback(&self.text, options(), Some(callback))
+      .into_offset_iter();
+    for (event, range) in parser.by_ref() {
+      if let Event::Start(Tag::Link { link_type, id, .. } | Tag::Image { link_type, id, .. }) =
+        event
+        && is_reference(link_type)
+      {
+        result
+          .references
+          .push(self.link_reference(id.into_string(), &range));
+      }
+    }
+    for (label, definition) in parser.reference_definitions().iter() {
+      result
+        .definitions
+        .push(self.link_reference(label.to_owned(), &definition.span));
+    }
+    drop(parser);
+    for link in broken {
+      if matches!(link.link_type, LinkType::Reference | LinkType::Collapsed) {
+        result
+          .references
+          .push(self.link_reference(link.reference.into_string(), &link.span));
+      }
+    }
+    result.definitions.sort();
+    result
+      .references
+      .sort_by_key(|reference| (reference.line, reference.start));
+    result
+  }
+
+  fn link_reference(&self, label: String, range: &Range<usize>) -> LinkReference {
+    let (line, start, end) = self.span(range);
+    LinkReference {
+      label,
+      line,
+      start,
+      end,
+    }
+  }
+
+  /// populates the given accumulators with all links and images in this Markdown text
+  pub fn references(&self, links: &mut Vec<Link>, images: &mut Vec<Image>) {
+    // index of the last HTML link whose closing tag hasn't been found yet
+    let mut open_html_link: Option<usize> = None;
+    // whether the HTML block being parsed is inside a multi-line comment
+    let mut in_html_comment = false;
+    for (event, range) in Parser::new_ext(&self.text, options()).into_offset_iter() {
+      match event {
+        Event::Html(html) => {
+          self.html_references(&html, range.start, &mut open_html_link, links, images);
+          self.html_block_references(&html, range.start, &mut in_html_comment, links, images);
+        }
+        Event::InlineHtml(html) => {
+          self.html_references(&html, range.start, &mut open_html_link, links, images);
+        }
+        event => self.markdown_reference(event, &range, links, images),
+      }
+    }
+  }
+
+  /// populates the given accumulators with the link or image that the given Markdown event starts
+  fn markdown_reference(
+    &self,
+    event: Event,
+    range: &Range<usize>,
+    links: &mut Vec<Link>,
+    images: &mut Vec<Image>,
+  ) {
+    match event {
+      Event::Start(Tag::Link {
+        link_type: LinkType::Autolink | LinkType::Email,
+        ..
+      }) => {}
+      Event::Start(Tag::Link { dest_url, .. }) => {
+        let (line, start, end) = self.span(range);
+        links.push(Link {
+          target: dest_url.into_string(),
+          line,
+          start,
+          end,
+        });
+      }
+      Event::Start(Tag::Image { dest_url, .. }) => {
+        let (line, start, end) = self.span(range);
+        images.push(Image {
+          src: dest_url.into_string(),
+          line,
+          start,
+          end,
+        });
+      }
+      _ => {}
+    }
+  }
+
+  /// populates the given accumulators with the links and images in the HTML tags of the given HTML text,
+  /// which starts at the given byte offset
+  fn html_references(
+    &self,
+    html: &str,
+    offset: usize,
+    open_html_link: &mut Option<usize>,
+    links: &mut Vec<Link>,
+    images: &mut Vec<Image>,
+  ) {
+    for captures in HTML_TAG_REGEX.captures_iter(html) {
+      let full_match = captures.get(0).unwrap();
+      let tag_range = offset + full_match.start()..offset + full_match.end();
+      let (line, start, end) = self.span(&tag_range);
+      if let Some(href) = captures.get(1) {
+        *open_html_link = Some(links.len());
+        links.push(Link {
+          target: href.as_str().to_owned(),
+          line,
+          start,
+          end,
+        });
+      } else if let Some(src) = captures.get(2) {
+        images.push(Image {
+          src: src.as_str().to_owned(),
+          line,
+          start,
+          end,
+        });
+      } else if let Some(index) = open_html_link.take() {
+        // closing tag of an HTML link: extend the link to include it if it is on the same line
+        let link = &mut links[index];
+        if link.line == line {
+          link.end = end;
+        }
+      }
+    }
+  }
+
+  /// populates the given accumulators with the Markdown links and images
+  /// that the given lines of an HTML block contain outside of HTML tags and comments,
+  /// since the Markdown parser leaves the content of HTML blocks unprocessed
+  fn html_block_references(
+    &self,
+    html: &str,
+    offset: usize,
+    in_comment: &mut bool,
+    links: &mut Vec<Link>,
+    images: &mut Vec<Image>,
+  ) {
+    let mut line_offset = offset;
+    for line in html.split_inclusive('\n') {
+      let line_start = line_offset;
+      line_offset += line.len();
+      let mut text = line.to_owned();
+      if *in_comment {
+        let Some(comment_end) = text.find("-->") else {
+          continue;
+        };
+        text.replace_range(..comment_end + 3, &" ".repeat(comment_end + 3));
+        *in_comment = false;
+      }
+      // blank out HTML tags and comments so that the remaining text keeps its positions
+      let mut text = HTML_MARKUP_REGEX
+        .replace_all(&text, |captures: &Captures| " ".repeat(captures[0].len()))
+        .into_owned();
+      if let Some(comment_start) = text.find("<!--") {
+        text.truncate(comment_start);
+        *in_comment = true;
+      }
+      let indent = text.len() - text.trim_start().len();
+      let text_start = line_start + indent;
+      for (event, range) in Parser::new_ext(&text[indent..], options()).into_offset_iter() {
+        let range = text_start + range.start..text_start + range.end;
+        self.markdown_reference(event, &range, links, images);
+      }
+    }
+  }
+
+  /// provides the line number, start column, and end column of the given byte range,
+  /// ranges spanning multiple lines end at the end of their first line
+  fn span(&self, range: &Range<usize>) -> (u32, u32, u32) {
+    let (index, start) = self.position(range.start);
+    let (end_index, end) = self.position(range.end);
+    let end = if end_index == index {
+      end
+    } else {
+      self.line_len(index)
+    };
+    (self.line_numbers[index], start as u32, end as u32)
+  }
+
+  /// provides the index of the line containing the given byte position and the column within that line
+  fn position(&self, pos: usize) -> (usize, usize) {
+    let index = self
+      .line_starts
+      .partition_point(|start| *start <= pos)
+      .max(1)
+      - 1;
+    (index, pos - self.line_starts[index])
+  }
+
+  /// provides the text of the line with the given index, without the newline
+  fn line_text(&self, index: usize) -> &str {
+    let start = self.line_starts[index];
+    &self.text[start..start + self.line_len(index)]
+  }
+
+  /// provides the length of the line with the given index, without the newline
+  fn line_len(&self, index: usize) -> usize {
+    let end = match self.line_starts.get(index + 1) {
+      Some(next_start) => next_start - 1,
+      None => self.text.len() - 1,
+    };
+    end - self.line_starts[index]
+  }
+}
+
+/// a fenced or indented code block
+#[derive(Debug, Eq, PartialEq)]
+pub struct CodeBlock {
+  /// line number of the first line, for fenced code blocks the opening fence
+  pub start: u32,
+  /// line number of the last line, for fenced code blocks the closing fence
+  pub end: u32,
+  /// false if this is a fenced code block without closing fence
+  pub closed: bool,
+}
+
+/// indicates whether the given line closes the code block opened by the given fence:
+/// it must consist of at least as many of the same fence characters
+fn is_closing_fence(opening: &str, line: &str) -> bool {
+  let Some(fence_char) = opening.chars().next() else {
+    return false;
+  };
+  let fence_len = opening.chars().take_while(|c| *c == fence_char).count();
+  let closing = line
+    .trim_start_matches(|c: char| c.is_whitespace() || c == '>')
+    .trim_end();
+  closing.len() >= fence_len && closing.chars().all(|c| c == fence_char)
+}
+
+/// a heading that starts a new section
+#[derive(Debug, Eq, PartialEq)]
+pub struct Heading {
+  /// line number of the heading text in the document
+  pub line: u32,
+  pub level: u8,
+  /// whether this heading is underlined with `===` or `---` on the following line
+  pub setext: bool,
+}
+
+/// indicates whether the given link type refers to a link reference definition
+fn is_reference(link_type: LinkType) -> bool {
+  matches!(
+    link_type,
+    LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut
+  )
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+  match level {
+    HeadingLevel::H1 => 1,
+    HeadingLevel::H2 => 2,
+    HeadingLevel::H3 => 3,
+    HeadingLevel::H4 => 4,
+    HeadingLevel::H5 => 5,
+    HeadingLevel::H6 => 6,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod code_blocks {
+    use super::super::{CodeBlock, Markdown};
+    use indoc::indoc;
+
+    fn code_blocks(text: &str) -> Vec<CodeBlock> {
+      Markdown::new(text.lines().enumerate().map(|(i, line)| (i as u32, line))).code_blocks()
+    }
+
+    #[test]
+    fn fences() {
+      let give = indoc! {"
+        # title
+        ```md
+        code
+        ```
+        ~~~
+        code
+        ~~~
+        "};
+      let want = vec![
+        CodeBlock {
+          start: 1,
+          end: 3,
+          closed: true,
+        },
+        CodeBlock {
+          start: 4,
+          end: 6,
+          closed: true,
+        },
+      ];
+      pretty::assert_eq!(code_blocks(give), want);
+    }
+
+    #[test]
+    fn nested_fences() {
+      let give = indoc! {"
+        ````md
+        ```rs
+        code
+        ```
+        ````
+        "};
+      let want = vec![CodeBlock {
+        start: 0,
+        end: 4,
+        closed: true,
+      }];
+      pretty::assert_eq!(code_blocks(give), want);
+    }
+
+    #[test]
+    fn indented() {
+      let give = indoc! {"
+        text
+
+            code
+            more code
+
+        text
+        "};
+      let want = vec![CodeBlock {
+        start: 2,
+        end: 3,
+        closed: true,
+      }];
+      pretty::assert_eq!(code_blocks(give), want);
+    }
+
+    #[test]
+    fn in_list() {
+      let give = indoc! {"
+        - point 1
+
+          ```go
+          code
+          ```
+        "};
+      let want = vec![CodeBlock {
+        start: 2,
+        end: 4,
+        closed: true,
+      }];
+      pretty::assert_eq!(code_blocks(give), want);
+    }
+
+    #[test]
+    fn unclosed() {
+      let give = indoc! {"
+        text
+        ~~~~
+        code
+        ~~~
+        "};
+      let want = vec![CodeBlock {
+        start: 1,
+        end: 3,
+        closed: false,
+      }];
+      pretty::assert_eq!(code_blocks(give), want);
+    }
+  }
+
+  mod headings {
+    use super::super::{Heading, Markdown};
+    use indoc::indoc;
+
+    fn headings(text: &str) -> Vec<Heading> {
+      Markdown::new(text.lines().enumerate().map(|(i, line)| (i as u32, line))).headings()
+    }
+
+    #[test]
+    fn atx() {
+      let give = indoc! {"
+        # one
+        text
+        ### three
+        ###
+        "};
+      let want = vec![
+        Heading {
+          line: 0,
+          level: 1,
+          setext: false,
+        },
+        Heading {
+          line: 2,
+          level: 3,
+          setext: false,
+        },
+        Heading {
+          line: 3,
+          level: 3,
+          setext: false,
+        },
+      ];
+      pretty::assert_eq!(headings(give), want);
+    }
+
+    #[test]
+    fn setext() {
+      let give = indoc! {"
+        One
+        ===
+
+        Two
+        ---
+        text
+        "};
+      let want = vec![
+        Heading {
+          line: 0,
+          level: 1,
+          setext: true,
+        },
+        Heading {
+          line: 3,
+          level: 2,
+          setext: true,
+        },
+      ];
+      pretty::assert_eq!(headings(give), want);
+    }
+
+    #[test]
+    fn hashtags_are_not_headings() {
+      let give = indoc! {"
+        # one
+        #hashtag
+        #another-hashtag text
+        "};
+      let want = vec![Heading {
+        line: 0,
+        level: 1,
+        setext: false,
+      }];
+      pretty::assert_eq!(headings(give), want);
+    }
+
+    #[test]
+    fn nested_headings_and_code_blocks() {
+      let give = indoc! {"
+        # one
+        > # quoted
+        - # listed
+
+        ```md
+        # code
+        ```
+        "};
+      let want = vec![Heading {
+        line: 0,
+        level: 1,
+        setext: false,
+      }];
+      pretty::assert_eq!(headings(give), want);
+    }
+  }
+
+  mod references {
+    use super::super::Markdown;
+    use crate::database::{Image, Link};
+    use big_s::S;
+
+    fn references(text: &str) -> (Vec<Link>, Vec<Image>) {
+      let markdown = Markdown::new(text.lines().map(|line| (12, line)));
+      let mut links = vec![];
+      let mut images = vec![];
+      markdown.references(&mut links, &mut images);
+      (links, images)
+    }
+
+    #[test]
+    fn link_md() {
+      let (links, _) =
+        references(r"an MD link: [one](one.md) and one to a section: [two pieces](two.md#pieces)!");
+      let want = vec![
+        Link {
+          target: S("one.md"),
+          line: 12,
+          start: 12,
+          end: 25,
+        },
+        Link {
+          target: S("two.md#pieces"),
+          line: 12,
+          start: 48,
+          end: 75,
+        },
+      ];
+      pretty::assert_eq!(links, want);
+    }
+
+    #[test]
+    fn link_html() {
+      let (links, _) = references(r#"an HTML link: <a href="two.md">two</a>"#);
+      let want = vec![Link {
+        target: S("two.md"),
+        line: 12,
+        start: 14,
+        end: 38,
+      }];
+      pretty::assert_eq!(links, want);
+    }
+
+    #[test]
+    fn link_with_parentheses() {
+      let (links, _) = references(r"[one](one_(1).md)");
+      let want = vec![Link {
+        target: S("one_(1).md"),
+        line: 12,
+        start: 0,
+        end: 17,
+      }];
+      pretty::assert_eq!(links, want);
+    }
+
+    #[test]
+    fn link_in_inline_code() {
+      let (links, images) = references(r"code: `[one](one.md)` and `![two](two.png)`");
+      assert_eq!(links, vec![]);
+      assert_eq!(images, vec![]);
+    }
+
+    #[test]
+    fn link_in_multi_backtick_code() {
+      let (links, images) = references(
+        r#"code: ``[one](one.md) with ` inside`` and ``<img src="two.png">`` but [three](three.md)"#,
+      );
+      let want = vec![Link {
+        target: S("three.md"),
+        line: 12,
+        start: 70,
+        end: 87,
+      }];
+      pretty::assert_eq!(links, want);
+      assert_eq!(images, vec![]);
+    }
+
+    #[test]
+    fn link_in_code_block() {
+      let (links, _) = references("```md\n[one](one.md)\n```");
+      assert_eq!(links, vec![]);
+    }
+
+    #[test]
+    fn reference_link() {
+      let (links, _) = references("see [one][1]\n\n[1]: one.md");
+      let want = vec![Link {
+        target: S("one.md"),
+        line: 12,
+        start: 4,
+        end: 12,
+      }];
+      pretty::assert_eq!(links, want);
+    }
+
+    #[test]
+    fn autolinks() {
+      let (links, _) = references("<https://google.com> and <me@example.com>");
+      assert_eq!(links, vec![]);
+    }
+
+    #[test]
+    fn img_md() {
+      let (_, images) = references(r"an MD image: ![zonk](zonk.md)");
+      let want = vec![Image {
+        src: S("zonk.md"),
+        line: 12,
+        start: 13,
+        end: 29,
+      }];
+      pretty::assert_eq!(images, want);
+    }
+
+    #[test]
+    fn img_html() {
+      let (_, images) = references(r#"<img src="zonk.md">"#);
+      let want = vec![Image {
+        src: S("zonk.md"),
+        line: 12,
+        start: 0,
+        end: 19,
+      }];
+      pretty::assert_eq!(images, want);
+    }
+
+    #[test]
+    fn md_in_html_block() {
+      let (links, images) =
+        references("<img src=\"one.png\">\n[two](two.md) <b>and</b> ![three](three.png)");
+      let want_links = vec![Link {
+        target: S("two.md"),
+        line: 12,
+        start: 0,
+        end: 13,
+      }];
+      pretty::assert_eq!(links, want_links);
+      let want_images = vec![
+        Image {
+          src: S("one.png"),
+          line: 12,
+          start: 0,
+          end: 19,
+        },
+        Image {
+          src: S("three.png"),
+          line: 12,
+          start: 25,
+          end: 44,
+        },
+      ];
+      pretty::assert_eq!(images, want_images);
+    }
+
+    #[test]
+    fn md_in_html_comment() {
+      let (links, images) =
+        references("<!-- [one](one.md)\n![two](two.png) -->\n<p>[three](three.md)</p>");
+      let want = vec![Link {
+        target: S("three.md"),
+        line: 12,
+        start: 3,
+        end: 20,
+      }];
+      pretty::assert_eq!(links, want);
+      assert_eq!(images, vec![]);
+    }
+
+    #[test]
+    fn img_html_extra_attributes() {
+      let (_, images) = references(r#"<img src="zonk.md" width="10" height="10">"#);
+      let want = vec![Image {
+        src: S("zonk.md"),
+        line: 12,
+        start: 0,
+        end: 42,
+      }];
+      pretty::assert_eq!(images, want);
+    }
+
+    #[test]
+    fn img_xml_nospace() {
+      let (_, images) = references(r#"<img src="zonk.md"/>"#);
+      let want = vec![Image {
+        src: S("zonk.md"),
+        line: 12,
+        start: 0,
+        end: 20,
+      }];
+      pretty::assert_eq!(images, want);
+    }
+
+    #[test]
+    fn img_xml_space() {
+      let (_, images) = references(r#"<img src="zonk.md" />"#);
+      let want = vec![Image {
+        src: S("zonk.md"),
+        line: 12,
+        start: 0,
+        end: 21,
+      }];
+      pretty::assert_eq!(images, want);
+    }
+  }
+}
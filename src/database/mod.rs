@@ -1,19 +1,33 @@
 //! Read/write access to the Markdown files making up the database.
 
+mod backlinks;
 mod directory;
 pub mod document;
+mod file_format;
 mod footnotes;
+mod fulltext;
 mod image;
+mod journal;
 mod line;
 mod link;
+mod link_references;
+pub mod markdown;
 pub mod section;
+mod slug;
 mod tikibase;
+mod windows1252;
 
 pub use crate::database::tikibase::Tikibase;
-pub use directory::{Directory, EntryType};
+pub use backlinks::{Backlink, Backlinks};
+pub use directory::{BrokenSymlink, Directory, EntryType};
 pub use document::Document;
+pub use file_format::FileFormat;
 pub use footnotes::{Footnote, Footnotes};
+pub use fulltext::{Hit, Query, SearchIndex};
 pub use image::Image;
+pub use journal::{JOURNAL_DIR, Journal, undo};
 pub use line::Line;
 pub use link::Link;
+pub use link_references::{LinkReference, LinkReferences};
+pub use markdown::Markdown;
 pub use section::Section;
@@ -1,8 +1,17 @@
-use super::{Image, Line, Link};
-use heck::ToKebabCase;
+use super::slug::slug;
+use super::{Image, Line, Link, Markdown};
+use crate::config::SlugStyle;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// a block of attributes at the end of a heading, e.g. "{#custom-id .class}"
+static ATTRIBUTES_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"\s*\{\s*((?:[#.][^\s{}]+|[^\s{}=]+=[^\s{}]*)(?:\s+(?:[#.][^\s{}]+|[^\s{}=]+=[^\s{}]*))*)\s*\}\s*$")
+    .unwrap()
+});
 
 /// a section in a document, from one heading to above the next heading
-#[derive(Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Section {
   /// the line number at which this section starts, 0-based
   pub line_number: u32,
@@ -22,9 +31,28 @@ pub struct Section {
 }
 
 impl Section {
-  /// provides the link anchor for this section, in GitHub format
-  pub fn anchor(&self) -> String {
-    format!("#{}", self.human_title().to_kebab_case())
+  /// provides the link anchor for this section:
+  /// the custom id if the title defines one, otherwise the title in the given slug style
+  pub fn anchor(&self, style: SlugStyle) -> String {
+    match self.custom_id() {
+      Some(id) => format!("#{id}"),
+      None => format!("#{}", slug(self.human_title(), style)),
+    }
+  }
+
+  /// provides the id that the title of this section defines via a "{#id}" attribute
+  pub fn custom_id(&self) -> Option<&str> {
+    let title = &self.title_line.text[self.title_text_start..];
+    if !title.trim_end().ends_with('}') {
+      return None;
+    }
+    let captures = ATTRIBUTES_REGEX.captures(title)?;
+    captures
+      .get(1)
+      .unwrap()
+      .as_str()
+      .split_whitespace()
+      .find_map(|attribute| attribute.strip_prefix('#'))
   }
 
   /// indicates whether this section contains no content
@@ -40,7 +68,14 @@ impl Section {
 
   /// provides a human-readable version of this section's title, e.g. "Hello" for a section with the title "# Hello"
   pub fn human_title(&self) -> &str {
-    &self.title_line.text[self.title_text_start..]
+    let title = &self.title_line.text[self.title_text_start..];
+    if !title.trim_end().ends_with('}') {
+      return title;
+    }
+    match ATTRIBUTES_REGEX.find(title) {
+      Some(attributes) => &title[..attributes.start()],
+      None => title,
+    }
   }
 
   /// returns the last line of this section
@@ -103,17 +138,40 @@ impl Section {
     (level as u8, start)
   }
 
+  /// replaces the title of this section with the given title line,
+  /// this also removes the underline of underlined titles
+  pub fn set_title<IS: Into<String>>(&mut self, title: IS) {
+    let underlined = !self.title_line.text.starts_with('#')
+      && self
+        .body
+        .first()
+        .is_some_and(|line| is_title_underline(&line.text));
+    if underlined {
+      self.body.remove(0);
+    }
+    let title: String = title.into();
+    let (level, start) = Self::parse_title(&title);
+    self.title_line = Line::from(title);
+    self.level = level;
+    self.title_text_start = start;
+  }
+
   /// adds a new line with the given text to this section
   pub fn push_line<IS: Into<String>>(&mut self, text: IS) {
     self.body.push(Line::from(text));
   }
 
+  /// provides the lines of this section together with their line numbers in the document
+  pub fn numbered_lines(&self) -> impl Iterator<Item = (u32, &str)> {
+    self
+      .lines()
+      .enumerate()
+      .map(|(i, line)| (self.line_number + i as u32, line.text.as_str()))
+  }
+
   /// populates the given accumulator with all references in this section
   pub fn references(&self, links: &mut Vec<Link>, images: &mut Vec<Image>) {
-    self.title_line.references(self.line_number, links, images);
-    for (i, line) in self.body.iter().enumerate() {
-      line.references(self.line_number + i as u32 + 1, links, images);
-    }
+    Markdown::new(self.numbered_lines()).references(links, images);
   }
 
   #[cfg(test)]
@@ -145,6 +203,12 @@ impl Section {
   }
 }
 
+/// indicates whether the given line underlines a title with `===` or `---`
+fn is_title_underline(text: &str) -> bool {
+  let text = text.trim();
+  !text.is_empty() && (text.chars().all(|c| c == '=') || text.chars().all(|c| c == '-'))
+}
+
 /// an iterator for Lines
 pub struct LinesIterator<'a> {
   title_line: &'a Line,
@@ -169,6 +233,8 @@ pub struct Builder {
   pub line_number: u32,
   title_line: String,
   body: Vec<String>,
+  /// level of a title underlined with `===` or `---`
+  setext_level: Option<u8>,
 }
 
 impl Builder {
@@ -178,6 +244,15 @@ impl Builder {
       title_line: title.into(),
       line_number,
       body: Vec::new(),
+      setext_level: None,
+    }
+  }
+
+  /// Provides a builder instance for a section whose title is underlined, i.e. has the given level.
+  pub fn setext<IS: Into<String>>(title: IS, line_number: u32, level: u8) -> Self {
+    Self {
+      setext_level: Some(level),
+      ..Self::new(title, line_number)
     }
   }
 
@@ -187,8 +262,27 @@ impl Builder {
 
   /// Provides the content this builder has accumulated.
   pub fn result(self) -> Section {
-    Section::new(self.line_number, self.title_line, self.body)
+    let mut section = Section::new(self.line_number, self.title_line, self.body);
+    if let Some(level) = self.setext_level {
+      section.level = level;
+      section.title_text_start =
+        section.title_line.text.len() - section.title_line.text.trim_start().len();
+    }
+    section
+  }
+}
+
+/// provides the given heading anchor with GitHub's numeric suffix if one of the given anchors already uses it,
+/// e.g. "#title-1" for the second heading "Title"
+pub fn unique_anchor(anchor: String, existing: &[String]) -> String {
+  if !existing.contains(&anchor) {
+    return anchor;
+  }
+  let mut counter = 1;
+  while existing.contains(&format!("{anchor}-{counter}")) {
+    counter += 1;
   }
+  format!("{anchor}-{counter}")
 }
 
 #[cfg(test)]
@@ -202,10 +296,33 @@ mod tests {
     let tests = vec![
       ("### foo", "#foo"),
       ("### A Complex Section", "#a-complex-section"),
+      ("### Custom {#my-id}", "#my-id"),
+      ("### Custom {.class #my-id lang=en}", "#my-id"),
+      ("### Only a class {.class}", "#only-a-class"),
+      ("### Set {a, b}", "#set-a-b"),
     ];
     for (give, want) in tests {
       let section = Section::with_title(give);
-      assert_eq!(section.anchor(), want);
+      assert_eq!(section.anchor(SlugStyle::Kebab), want);
+    }
+  }
+
+  mod unique_anchor {
+    use super::super::unique_anchor;
+    use big_s::S;
+
+    #[test]
+    fn new_anchor() {
+      assert_eq!(unique_anchor(S("#one"), &[S("#two")]), "#one");
+    }
+
+    #[test]
+    fn duplicate_anchors() {
+      assert_eq!(unique_anchor(S("#one"), &[S("#one")]), "#one-1");
+      assert_eq!(
+        unique_anchor(S("#one"), &[S("#one"), S("#one-1")]),
+        "#one-2"
+      );
     }
   }
 
@@ -242,7 +359,13 @@ mod tests {
 
   #[test]
   fn human_title() {
-    let tests = vec![("# title", "title"), ("###### title", "title"), ("###", "")];
+    let tests = vec![
+      ("# title", "title"),
+      ("###### title", "title"),
+      ("###", ""),
+      ("### title {#custom-id}", "title"),
+      ("### set {a, b}", "set {a, b}"),
+    ];
     for (give, want) in tests {
       let section = Section::with_title(give);
       assert_eq!(section.human_title(), want);
@@ -373,6 +496,29 @@ mod tests {
     }
   }
 
+  mod set_title {
+    use crate::database::{Line, Section};
+
+    #[test]
+    fn atx() {
+      let mut section = Section::new(3, "### one", vec!["text"]);
+      section.set_title("## two");
+      assert_eq!(section.title_line, Line::from("## two"));
+      assert_eq!(section.human_title(), "two");
+      assert_eq!(section.level, 2);
+      assert_eq!(section.body, vec![Line::from("text")]);
+    }
+
+    #[test]
+    fn setext() {
+      let mut section = Section::new(3, "one", vec!["---", "text"]);
+      section.set_title("### one");
+      assert_eq!(section.title_line, Line::from("### one"));
+      assert_eq!(section.level, 3);
+      assert_eq!(section.body, vec![Line::from("text")]);
+    }
+  }
+
   #[test]
   fn text() {
     let section = Section::new(0, "### welcome", vec!["", "content"]);
@@ -0,0 +1,125 @@
+use super::markdown;
+use crate::config::SlugStyle;
+use heck::ToKebabCase;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// characters that GitHub removes from headings: everything except letters, marks, numbers, connectors, spaces, and hyphens
+static GITHUB_REMOVED: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"[^\p{L}\p{M}\p{N}\p{Pc} -]").unwrap());
+
+/// characters that GitLab removes from headings: everything except word characters, spaces, and hyphens
+static GITLAB_REMOVED: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\w -]").unwrap());
+
+/// runs of hyphens
+static HYPHENS: Lazy<Regex> = Lazy::new(|| Regex::new(r"-{2,}").unwrap());
+
+/// provides the anchor, without leading "#", that the given Markdown renderer creates for a heading with the given title
+pub fn slug(title: &str, style: SlugStyle) -> String {
+  match style {
+    SlugStyle::Kebab => title.to_kebab_case(),
+    SlugStyle::Github => github(&markdown::heading_text(title)),
+    SlugStyle::Gitlab => gitlab(&markdown::heading_text(title)),
+    SlugStyle::Pandoc => pandoc(&markdown::heading_text(title)),
+  }
+}
+
+/// the algorithm of github-slugger
+fn github(text: &str) -> String {
+  GITHUB_REMOVED
+    .replace_all(&text.trim().to_lowercase(), "")
+    .replace(' ', "-")
+}
+
+/// the algorithm of GitLab's table of contents filter
+fn gitlab(text: &str) -> String {
+  let lowercase = text.trim().to_lowercase();
+  let kept = GITLAB_REMOVED.replace_all(&lowercase, "");
+  HYPHENS
+    .replace_all(&kept.replace(' ', "-"), "-")
+    .into_owned()
+}
+
+/// the algorithm of Pandoc's `auto_identifiers` extension
+fn pandoc(text: &str) -> String {
+  let kept: String = text
+    .to_lowercase()
+    .chars()
+    .filter(|c| c.is_alphanumeric() || c.is_whitespace() || matches!(c, '_' | '-' | '.'))
+    .collect();
+  let joined = kept.split_whitespace().collect::<Vec<_>>().join("-");
+  let result = joined.trim_start_matches(|c: char| !c.is_alphabetic());
+  if result.is_empty() {
+    return "section".to_owned();
+  }
+  result.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod slug {
+    use super::super::slug;
+    use crate::config::SlugStyle;
+
+    #[test]
+    fn kebab() {
+      let tests = vec![
+        ("A Complex Section", "a-complex-section"),
+        ("fooBar_baz", "foo-bar-baz"),
+      ];
+      for (give, want) in tests {
+        assert_eq!(slug(give, SlugStyle::Kebab), want);
+      }
+    }
+
+    #[test]
+    fn github() {
+      let tests = vec![
+        ("A Complex Section", "a-complex-section"),
+        ("fooBar_baz", "foobar_baz"),
+        ("What's new?", "whats-new"),
+        ("C++ and C#", "c-and-c"),
+        ("Über Straße", "über-straße"),
+        ("one -- two", "one----two"),
+        ("the `code` and **bold** text", "the-code-and-bold-text"),
+        ("a [link](https://example.com/page)", "a-link"),
+        ("2024 review", "2024-review"),
+      ];
+      for (give, want) in tests {
+        assert_eq!(slug(give, SlugStyle::Github), want, "{give}");
+      }
+    }
+
+    #[test]
+    fn gitlab() {
+      let tests = vec![
+        ("A Complex Section", "a-complex-section"),
+        ("fooBar_baz", "foobar_baz"),
+        ("What's new?", "whats-new"),
+        ("C++ and C#", "c-and-c"),
+        ("Über Straße", "über-straße"),
+        ("one -- two", "one-two"),
+      ];
+      for (give, want) in tests {
+        assert_eq!(slug(give, SlugStyle::Gitlab), want, "{give}");
+      }
+    }
+
+    #[test]
+    fn pandoc() {
+      let tests = vec![
+        ("A Complex Section", "a-complex-section"),
+        ("fooBar_baz", "foobar_baz"),
+        ("What's new?", "whats-new"),
+        ("Version 1.2", "version-1.2"),
+        ("2024 review", "review"),
+        ("one   two", "one-two"),
+        ("123", "section"),
+      ];
+      for (give, want) in tests {
+        assert_eq!(slug(give, SlugStyle::Pandoc), want, "{give}");
+      }
+    }
+  }
+}
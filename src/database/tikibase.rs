@@ -1,19 +1,68 @@
-use super::{Directory, Document};
+use super::{Backlinks, Directory, Document, Journal, SearchIndex};
 use crate::Config;
 use crate::check::Issue;
 use big_s::S;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use core::cell::OnceCell;
+use fs_err as fs;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct Tikibase {
   pub root: Utf8PathBuf,
   pub dir: Directory,
+  /// if provided, the original content of changed files gets stored in this directory
+  pub backup_dir: Option<Utf8PathBuf>,
+  /// if provided, records the changed files so that the changes can be undone
+  pub journal: Option<Journal>,
+  /// if set, changed documents only get updated in memory and not saved to disk
+  pub preview: bool,
+  /// the index of all references between the files, built when first needed
+  backlinks: OnceCell<Backlinks>,
 }
 
 impl Tikibase {
   pub fn load<P: Into<Utf8PathBuf>>(root: P) -> Result<Self, Vec<Issue>> {
     let root = root.into();
     let dir = Directory::load(&root, S(""), Config::default())?;
-    Ok(Self { root, dir })
+    Ok(Self {
+      root,
+      dir,
+      backup_dir: None,
+      journal: None,
+      preview: false,
+      backlinks: OnceCell::new(),
+    })
+  }
+
+  /// stores the original content of all files that get changed from now on
+  /// in a new subdirectory of ".tikibase/backup"
+  pub fn enable_backups(&mut self) {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
+    self.backup_dir = Some(self.root.join(BACKUP_DIR).join(timestamp.to_string()));
+  }
+
+  /// records all files that get changed from now on in a new journal
+  pub fn start_journal(&mut self) {
+    self.journal = Some(Journal::start(&self.root));
+  }
+
+  /// stores the journal started by `start_journal`,
+  /// replacing the journal of the previous fix
+  pub fn finish_journal(&mut self) -> Result<(), Issue> {
+    match self.journal.take() {
+      Some(journal) => journal.finish(&self.root),
+      None => Ok(()),
+    }
+  }
+
+  /// provides the index of all references between the files in this Tikibase
+  pub fn backlinks(&self) -> &Backlinks {
+    self
+      .backlinks
+      .get_or_init(|| Backlinks::from_dir(&self.dir))
   }
 
   pub fn get_dir(&self, relative_path: &str) -> Option<&Directory> {
@@ -24,15 +73,153 @@ impl Tikibase {
     self.dir.get_doc(relative_path)
   }
 
-  /// provides the document with the given relative filename as a mutable reference
+  /// provides the document with the given relative filename as a mutable reference,
+  /// the index of references picks up changes to it when it gets saved
   pub fn get_doc_mut(&mut self, path: &str) -> Option<&mut Document> {
     self.dir.get_doc_mut(path)
   }
+
+  /// replaces the existing document that has the same path as the given document with it
+  pub fn replace_doc(&mut self, doc: Document) {
+    let path = doc.relative_path.clone();
+    if let Some(existing) = self.dir.get_doc_mut(&path) {
+      *existing = doc;
+    }
+    self.update_backlinks(&path);
+  }
+
+  /// updates the index of references, if it exists, with the current references of the given document
+  fn update_backlinks(&mut self, relative_path: &str) {
+    if let Some(backlinks) = self.backlinks.get_mut() {
+      backlinks.update_doc(&self.dir, relative_path);
+    }
+  }
+
+  /// persists the document with the given path to disk after updating its derived data,
+  /// backs up and journals its original content first if enabled
+  pub fn save_doc(&mut self, relative_path: &str) -> Result<(), Issue> {
+    if self.preview {
+      if let Some(doc) = self.dir.get_doc_mut(relative_path) {
+        doc.refresh();
+      }
+      self.update_backlinks(relative_path);
+      return Ok(());
+    }
+    if self.get_doc(relative_path).is_none() {
+      return Ok(());
+    }
+    if let Some(backup_dir) = &self.backup_dir {
+      backup(
+        &self.root.join(relative_path),
+        &backup_dir.join(relative_path),
+      )?;
+    }
+    if let Some(journal) = &mut self.journal {
+      journal.record(&self.root, relative_path)?;
+    }
+    let Some(doc) = self.dir.get_doc_mut(relative_path) else {
+      return Ok(());
+    };
+    doc.refresh();
+    let saved = doc.save(&self.root);
+    if saved.is_ok() {
+      // the saved file no longer contains the filtered-out "occurrences" section
+      doc.old_occurrences_section = None;
+    }
+    self.update_backlinks(relative_path);
+    saved
+  }
+
+  /// replaces the document with the given path with its current content on disk
+  pub fn reload_doc(&mut self, relative_path: &str) -> Result<(), Issue> {
+    let doc = Document::load(self.root.join(relative_path), relative_path.into())?;
+    self.replace_doc(doc);
+    Ok(())
+  }
+
+  /// provides the full-text search index over all documents in this Tikibase
+  pub fn search_index(&self) -> SearchIndex<'_> {
+    SearchIndex::from_dir(&self.dir)
+  }
+}
+
+/// the directory inside the Tikibase root that stores backups
+pub const BACKUP_DIR: &str = ".tikibase/backup";
+
+/// copies the given file to the given backup location,
+/// unless an earlier change already backed it up
+fn backup(file: &Utf8Path, backup_file: &Utf8Path) -> Result<(), Issue> {
+  if backup_file.exists() {
+    return Ok(());
+  }
+  copy_file(file, backup_file)
+}
+
+/// copies the given file to the given location, creating missing directories
+pub(super) fn copy_file(file: &Utf8Path, copy: &Utf8Path) -> Result<(), Issue> {
+  let result = match copy.parent() {
+    Some(parent) => fs::create_dir_all(parent),
+    None => Ok(()),
+  }
+  .and_then(|()| fs::copy(file, copy).map(|_bytes| ()));
+  result.map_err(|err| Issue::CannotWriteFile {
+    file: copy.to_string(),
+    message: err.to_string(),
+  })
 }
 
 #[cfg(test)]
 mod tests {
 
+  mod save_doc {
+    use crate::database::Tikibase;
+    use crate::test;
+
+    #[test]
+    fn with_backup() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("sub/1.md", "# One\n\n### Old\n\ntext\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      base.enable_backups();
+      let backup_dir = base.backup_dir.clone().unwrap();
+      assert!(backup_dir.starts_with(dir.path().join(".tikibase/backup")));
+      let doc = base.get_doc_mut("sub/1.md").unwrap();
+      doc.content_sections[0].title_line.text = "### New".into();
+      base.save_doc("sub/1.md").unwrap();
+      let doc = base.get_doc_mut("sub/1.md").unwrap();
+      doc.content_sections[0].title_line.text = "### Newer".into();
+      base.save_doc("sub/1.md").unwrap();
+      let want = "# One\n\n### Newer\n\ntext\n";
+      assert_eq!(test::load_file("sub/1.md", dir.path()), want);
+      let want = "# One\n\n### Old\n\ntext\n";
+      assert_eq!(test::load_file("sub/1.md", &backup_dir), want);
+    }
+
+    #[test]
+    fn preview() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\n### Old\n\ntext\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      base.preview = true;
+      let doc = base.get_doc_mut("1.md").unwrap();
+      doc.content_sections[0].title_line.text = "### New".into();
+      base.save_doc("1.md").unwrap();
+      let want = "# One\n\n### New\n\ntext\n";
+      assert_eq!(base.get_doc("1.md").unwrap().text(), want);
+      let want = "# One\n\n### Old\n\ntext\n";
+      assert_eq!(test::load_file("1.md", dir.path()), want);
+    }
+
+    #[test]
+    fn without_backup() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\ntext\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      base.save_doc("1.md").unwrap();
+      assert!(!dir.path().join(".tikibase").exists());
+    }
+  }
+
   mod get_doc {
     use crate::database::Tikibase;
     use crate::test;
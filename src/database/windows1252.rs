@@ -0,0 +1,71 @@
+//! Windows-1252, the encoding of most text files on Western Windows systems that aren't UTF-8.
+
+/// the characters that the bytes 0x80 to 0x9F encode,
+/// the five bytes that Windows-1252 leaves undefined map to the control characters with their value
+const HIGH_CONTROLS: [char; 32] = [
+  '\u{20ac}', '\u{81}', '\u{201a}', '\u{192}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+  '\u{2c6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8d}', '\u{17d}', '\u{8f}', '\u{90}',
+  '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{2dc}',
+  '\u{2122}', '\u{161}', '\u{203a}', '\u{153}', '\u{9d}', '\u{17e}', '\u{178}',
+];
+
+/// provides the text that the given Windows-1252 bytes encode
+pub fn decode(bytes: &[u8]) -> String {
+  bytes
+    .iter()
+    .map(|byte| match byte {
+      0x80..=0x9f => HIGH_CONTROLS[usize::from(byte - 0x80)],
+      _ => char::from(*byte),
+    })
+    .collect()
+}
+
+/// provides the Windows-1252 bytes for the given text,
+/// or the first character that Windows-1252 cannot represent
+pub fn encode(text: &str) -> Result<Vec<u8>, char> {
+  text
+    .chars()
+    .map(|c| match HIGH_CONTROLS.iter().position(|high| *high == c) {
+      Some(index) => Ok(0x80 + index as u8),
+      None => match u8::try_from(c) {
+        Ok(byte) if !(0x80..=0x9f).contains(&byte) => Ok(byte),
+        _ => Err(c),
+      },
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod decode {
+
+    #[test]
+    fn latin1_range() {
+      let have = super::super::decode(b"caf\xe9 \xa0");
+      assert_eq!(have, "caf\u{e9} \u{a0}");
+    }
+
+    #[test]
+    fn high_controls() {
+      let have = super::super::decode(b"\x93quoted\x94 \x80 \x85 \x81");
+      assert_eq!(have, "\u{201c}quoted\u{201d} \u{20ac} \u{2026} \u{81}");
+    }
+  }
+
+  mod encode {
+
+    #[test]
+    fn roundtrip() {
+      let bytes: Vec<u8> = (0..=255).collect();
+      let have = super::super::encode(&super::super::decode(&bytes));
+      assert_eq!(have, Ok(bytes));
+    }
+
+    #[test]
+    fn unrepresentable() {
+      let have = super::super::encode("caf\u{e9} \u{2192}");
+      assert_eq!(have, Err('\u{2192}'));
+    }
+  }
+}
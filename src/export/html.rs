@@ -0,0 +1,379 @@
+use crate::check::Issue;
+use crate::commands::Outcome;
+use crate::config::SlugStyle;
+use crate::database::{Backlinks, Directory, Document, EntryType, Section, markdown, section};
+use crate::{Tikibase, fspath};
+use camino::Utf8Path;
+use fs_err as fs;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{CowStr, Event, Parser, Tag, html};
+use regex::{Captures, Regex};
+use std::collections::BTreeSet;
+
+/// the link target of HTML anchor tags: `<a href="...">`
+static HTML_HREF_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"(<a\s[^>]*?href=")([^"]*)(")"#).unwrap());
+
+/// renders all documents in the given Tikibase as a static website into the given directory
+pub fn export(base: &Tikibase, outdir: &Utf8Path) -> Outcome {
+  let mut outcome = Outcome::default();
+  let mut docs = vec![];
+  collect_docs(&base.dir, &mut docs);
+  docs.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+  let backlinks = base.backlinks();
+  for doc in &docs {
+    let page = render_page(
+      doc,
+      &backlink_entries(base, backlinks, doc),
+      base.dir.config.slug_style(),
+    );
+    write(
+      outdir,
+      &html_path(&doc.relative_path),
+      &page,
+      &mut outcome.issues,
+    );
+  }
+  // the resources that documents reference
+  let resources: BTreeSet<&str> = backlinks
+    .targets()
+    .filter(|path| EntryType::from_str(path) == EntryType::Resource && base.dir.has_resource(path))
+    .collect();
+  for resource in resources {
+    let target = outdir.join(resource);
+    let result =
+      create_parent_dir(&target).and_then(|()| fs::copy(base.root.join(resource), &target));
+    if let Err(err) = result {
+      outcome.issues.push(Issue::CannotWriteFile {
+        file: target.to_string(),
+        message: err.to_string(),
+      });
+    }
+  }
+  // an existing index.md provides the start page
+  if base.get_doc("index.md").is_none() {
+    write(
+      outdir,
+      "index.html",
+      &render_index(&docs),
+      &mut outcome.issues,
+    );
+  }
+  outcome
+}
+
+/// populates the given list with all documents in the given directory and its subdirectories
+fn collect_docs<'a>(dir: &'a Directory, docs: &mut Vec<&'a Document>) {
+  docs.extend(dir.docs.values());
+  for subdir in dir.dirs.values() {
+    collect_docs(subdir, docs);
+  }
+}
+
+/// provides the links to the documents that link to the given document, as (path, title) pairs
+fn backlink_entries(
+  base: &Tikibase,
+  backlinks: &Backlinks,
+  doc: &Document,
+) -> Vec<(String, String)> {
+  let mut result: Vec<(String, String)> = vec![];
+  for backlink in backlinks.to(&doc.relative_path) {
+    let Some(source) = base.get_doc(&backlink.source) else {
+      continue;
+    };
+    let path = html_path(&fspath::encode(&fspath::relative(
+      &doc.relative_path,
+      &source.relative_path,
+    )));
+    if !result.iter().any(|(existing, _)| existing == &path) {
+      result.push((path, source.human_title().to_owned()));
+    }
+  }
+  result
+}
+
+/// provides the complete HTML page for the given document
+fn render_page(doc: &Document, backlinks: &[(String, String)], slug_style: SlugStyle) -> String {
+  let mut result = page_start(doc.human_title());
+  result.push_str(&render_markdown(&doc.text(), slug_style));
+  if !backlinks.is_empty() {
+    result.push_str("<nav class=\"backlinks\">\n<h2>Backlinks</h2>\n<ul>\n");
+    for (path, title) in backlinks {
+      result.push_str(&format!(
+        "<li><a href=\"{}\">{}</a></li>\n",
+        escape(path),
+        escape(title)
+      ));
+    }
+    result.push_str("</ul>\n</nav>\n");
+  }
+  result.push_str(PAGE_END);
+  result
+}
+
+/// provides the HTML page listing all given documents
+fn render_index(docs: &[&Document]) -> String {
+  let mut result = page_start("Index");
+  result.push_str("<h1>Index</h1>\n<ul>\n");
+  for doc in docs {
+    result.push_str(&format!(
+      "<li><a href=\"{}\">{}</a></li>\n",
+      escape(&html_path(&fspath::encode(&doc.relative_path))),
+      escape(doc.human_title())
+    ));
+  }
+  result.push_str("</ul>\n");
+  result.push_str(PAGE_END);
+  result
+}
+
+/// provides the given Markdown text as HTML,
+/// with links to Markdown documents pointing to the exported HTML pages
+/// and headings carrying the same anchors that Tikibase uses to check links
+fn render_markdown(text: &str, slug_style: SlugStyle) -> String {
+  let parser = Parser::new_ext(text, markdown::options());
+  let mut anchors: Vec<String> = vec![];
+  let events = parser.into_offset_iter().map(|(event, range)| match event {
+    Event::Start(Tag::Link {
+      link_type,
+      dest_url,
+      title,
+      id,
+    }) => Event::Start(Tag::Link {
+      link_type,
+      dest_url: CowStr::from(html_target(&dest_url)),
+      title,
+      id,
+    }),
+    Event::Start(Tag::Heading {
+      level,
+      id: None,
+      classes,
+      attrs,
+    }) => {
+      let title_line = text[range].lines().next().unwrap_or_default();
+      let anchor = section::unique_anchor(
+        Section::new(0, title_line, vec![]).anchor(slug_style),
+        &anchors,
+      );
+      anchors.push(anchor.clone());
+      Event::Start(Tag::Heading {
+        level,
+        id: Some(CowStr::from(anchor[1..].to_owned())),
+        classes,
+        attrs,
+      })
+    }
+    Event::Html(html) => Event::Html(CowStr::from(html_hrefs(&html))),
+    Event::InlineHtml(html) => Event::InlineHtml(CowStr::from(html_hrefs(&html))),
+    event => event,
+  });
+  let mut result = String::new();
+  html::push_html(&mut result, events);
+  result
+}
+
+/// provides the link target to use in the HTML export for the given link target in a document
+fn html_target(target: &str) -> String {
+  if fspath::is_external(target) || target.starts_with("mailto:") {
+    return target.to_owned();
+  }
+  match target.split_once('#') {
+    Some((file, anchor)) => format!("{}#{anchor}", html_path(file)),
+    None => html_path(target),
+  }
+}
+
+/// provides the given HTML with links to Markdown documents pointing to the exported HTML pages
+fn html_hrefs(html: &str) -> String {
+  HTML_HREF_REGEX
+    .replace_all(html, |captures: &Captures| {
+      format!(
+        "{}{}{}",
+        &captures[1],
+        html_target(&captures[2]),
+        &captures[3]
+      )
+    })
+    .into_owned()
+}
+
+/// provides the path of the HTML page for the document with the given path
+fn html_path(path: &str) -> String {
+  match path.strip_suffix(".md") {
+    Some(base) => format!("{base}.html"),
+    None => path.to_owned(),
+  }
+}
+
+fn page_start(title: &str) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+    escape(title)
+  )
+}
+
+const PAGE_END: &str = "</body>\n</html>\n";
+
+/// escapes the given text for use in HTML content and attributes
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn create_parent_dir(path: &Utf8Path) -> std::io::Result<()> {
+  match path.parent() {
+    Some(parent) => fs::create_dir_all(parent),
+    None => Ok(()),
+  }
+}
+
+/// writes the given content into the file with the given path inside the given directory
+fn write(outdir: &Utf8Path, path: &str, content: &str, issues: &mut Vec<Issue>) {
+  let target = outdir.join(path);
+  if let Err(err) = create_parent_dir(&target).and_then(|()| fs::write(&target, content)) {
+    issues.push(Issue::CannotWriteFile {
+      file: target.to_string(),
+      message: err.to_string(),
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod export {
+    use crate::{Tikibase, test};
+    use indoc::indoc;
+
+    #[test]
+    fn website() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\n[Two](sub/2.md#the-details)\n", dir.path());
+      let two = indoc! {"
+        # Two
+
+        ### The Details
+
+        ![image](../img.png)
+        "};
+      test::create_file("sub/2.md", two, dir.path());
+      test::create_file("img.png", "image", dir.path());
+      test::create_file("unused.png", "image", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let outdir = camino_tempfile::tempdir().unwrap();
+      let have = super::super::export(&base, outdir.path());
+      assert!(have.issues.is_empty());
+      let want = indoc! {r#"
+        <!DOCTYPE html>
+        <html>
+        <head>
+        <meta charset="utf-8">
+        <title>One</title>
+        </head>
+        <body>
+        <h1 id="one">One</h1>
+        <p><a href="sub/2.html#the-details">Two</a></p>
+        </body>
+        </html>
+        "#};
+      pretty::assert_eq!(test::load_file("1.html", outdir.path()), want);
+      let want = indoc! {r#"
+        <!DOCTYPE html>
+        <html>
+        <head>
+        <meta charset="utf-8">
+        <title>Two</title>
+        </head>
+        <body>
+        <h1 id="two">Two</h1>
+        <h3 id="the-details">The Details</h3>
+        <p><img src="../img.png" alt="image" /></p>
+        <nav class="backlinks">
+        <h2>Backlinks</h2>
+        <ul>
+        <li><a href="../1.html">One</a></li>
+        </ul>
+        </nav>
+        </body>
+        </html>
+        "#};
+      pretty::assert_eq!(test::load_file("sub/2.html", outdir.path()), want);
+      assert!(outdir.path().join("img.png").exists());
+      assert!(!outdir.path().join("unused.png").exists());
+      let index = test::load_file("index.html", outdir.path());
+      assert!(
+        index
+          .contains("<li><a href=\"1.html\">One</a></li>\n<li><a href=\"sub/2.html\">Two</a></li>")
+      );
+    }
+  }
+
+  mod html_target {
+    use super::super::html_target;
+
+    #[test]
+    fn document_with_anchor() {
+      assert_eq!(html_target("sub/one.md#section"), "sub/one.html#section");
+    }
+
+    #[test]
+    fn document() {
+      assert_eq!(html_target("../one.md"), "../one.html");
+    }
+
+    #[test]
+    fn anchor_only() {
+      assert_eq!(html_target("#section"), "#section");
+    }
+
+    #[test]
+    fn resource() {
+      assert_eq!(html_target("image.png"), "image.png");
+    }
+
+    #[test]
+    fn external() {
+      assert_eq!(
+        html_target("https://foo.com/one.md"),
+        "https://foo.com/one.md"
+      );
+    }
+  }
+
+  mod html_hrefs {
+    use super::super::html_hrefs;
+
+    #[test]
+    fn document() {
+      let give = r#"<p><a class="x" href="sub/one.md#section">one</a> <img src="two.md"></p>"#;
+      let want = r#"<p><a class="x" href="sub/one.html#section">one</a> <img src="two.md"></p>"#;
+      assert_eq!(html_hrefs(give), want);
+    }
+
+    #[test]
+    fn external() {
+      let give = r#"<a href="https://foo.com/one.md">one</a>"#;
+      assert_eq!(html_hrefs(give), give);
+    }
+  }
+
+  mod backlink_entries {
+    use crate::{Tikibase, test};
+    use big_s::S;
+
+    #[test]
+    fn encodes_paths() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n", dir.path());
+      test::create_file("two words.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let doc = base.get_doc("1.md").unwrap();
+      let have = super::super::backlink_entries(&base, base.backlinks(), doc);
+      assert_eq!(have, vec![(S("two%20words.html"), S("Two"))]);
+    }
+  }
+}
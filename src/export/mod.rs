@@ -0,0 +1,3 @@
+//! Exporting a Tikibase into other formats
+
+pub mod html;
@@ -0,0 +1,460 @@
+use super::Fix::{AddedBacklink, RemovedStaleBacklink};
+use super::missing_links::link_title;
+use crate::check::{Issue, Location};
+use crate::database::{Document, Line, Markdown, Section, Tikibase};
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{fix, fspath};
+
+/// regenerates the backlinks section of the document at the given location
+pub fn regenerate(
+  base: &mut Tikibase,
+  location: Location,
+  target: String,
+  section_title: &str,
+) -> fix::Result {
+  let entries = match entries(base, &location.file, section_title) {
+    Ok(entries) => entries,
+    Err(issue) => return Failed(issue),
+  };
+  if entries.is_empty() {
+    // the document already links to all documents that link to it
+    return Unfixable;
+  }
+  let doc = base.get_doc_mut(&location.file).unwrap();
+  let line = update_section(doc, section_title, entries);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(AddedBacklink {
+    location: Location {
+      file: location.file,
+      line: line.unwrap_or_default(),
+      start: 0,
+      end: section_title.len() as u32,
+    },
+    target,
+    section_title: human_title(section_title).into(),
+  })
+}
+
+/// removes the entry pointing to the given path from the backlinks section of the document at the given location
+pub fn remove_entry(base: &mut Tikibase, location: Location, path: String) -> fix::Result {
+  let Some(section_title) = base.dir.config.backlinks_section.clone() else {
+    return remove_occurrence(base, location, path);
+  };
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let Some(section) = doc.section_with_title(&section_title) else {
+    return Unfixable;
+  };
+  let entries: Vec<String> = section
+    .body
+    .iter()
+    .filter(|line| !line.text.is_empty() && !points_to(line, &path))
+    .map(|line| line.text.clone())
+    .collect();
+  update_section(doc, &section_title, entries);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(RemovedStaleBacklink { location, path })
+}
+
+/// removes the entry pointing to the given path from the "occurrences" section of the document at the given location
+fn remove_occurrence(base: &mut Tikibase, location: Location, path: String) -> fix::Result {
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let Some(mut section) = doc.old_occurrences_section.take() else {
+    return Unfixable;
+  };
+  section.body.retain(|line| !points_to(line, &path));
+  trim_trailing_empty_lines(&mut section);
+  // the remaining entries become content of the document again,
+  // so that they don't get reported as missing links
+  let last_section = doc.last_section_mut();
+  trim_trailing_empty_lines(last_section);
+  last_section.push_line("");
+  doc.content_sections.push(section);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(RemovedStaleBacklink { location, path })
+}
+
+/// provides the lines of the backlinks section for the document with the given path:
+/// one line per linking document that the document doesn't link to elsewhere, sorted by path
+pub fn entries(base: &Tikibase, doc_path: &str, section_title: &str) -> Result<Vec<String>, Issue> {
+  let title_regex = base.dir.config.title_regex()?;
+  let format = base.dir.config.backlinks_format();
+  let doc = base.get_doc(doc_path).unwrap();
+  let backlinks = base.backlinks();
+  let mut entries: Vec<(String, String)> = vec![];
+  for backlink in backlinks.to(doc_path) {
+    let Some(source_doc) = base.get_doc(&backlink.source) else {
+      continue;
+    };
+    // links in generated sections are backlinks themselves
+    if in_generated_section(source_doc, backlink.line, section_title) {
+      continue;
+    }
+    let path = fspath::relative(doc_path, &backlink.source);
+    if entries.iter().any(|(existing, _)| existing == &path)
+      || doc.contains_reference_outside_section(&path, section_title, base.dir.config.base_path())
+    {
+      continue;
+    }
+    let title = link_title(source_doc.human_title(), title_regex.as_ref())?;
+    let entry = format
+      .replace("{title}", &title)
+      .replace("{path}", &fspath::encode(&path));
+    entries.push((path, entry));
+  }
+  entries.sort();
+  Ok(entries.into_iter().map(|(_path, entry)| entry).collect())
+}
+
+/// indicates whether the given line of the given document is part of its backlinks section with the given title
+/// or its "occurrences" section
+fn in_generated_section(doc: &Document, line: u32, section_title: &str) -> bool {
+  [
+    doc.section_with_title(section_title),
+    doc.section_with_human_title("occurrences"),
+  ]
+  .into_iter()
+  .flatten()
+  .any(|section| (section.line_number..=section.last_line_abs()).contains(&line))
+}
+
+/// replaces the body of the section with the given title in the given document with the given entries,
+/// appends such a section if the document doesn't contain one,
+/// and removes it if there are no entries,
+/// provides the line number of the resulting section
+pub fn update_section(
+  doc: &mut Document,
+  section_title: &str,
+  entries: Vec<String>,
+) -> Option<u32> {
+  let pos = doc
+    .content_sections
+    .iter()
+    .position(|section| section.title_line.text == section_title);
+  if entries.is_empty() {
+    if let Some(pos) = pos {
+      doc.content_sections.remove(pos);
+      trim_trailing_empty_lines(doc.last_section_mut());
+    }
+    return None;
+  }
+  let mut body = vec![Line::from("")];
+  body.extend(entries.into_iter().map(Line::from));
+  match pos {
+    Some(pos) => {
+      let is_last = pos == doc.content_sections.len() - 1;
+      let section = &mut doc.content_sections[pos];
+      if !is_last {
+        body.push(Line::from(""));
+      }
+      section.body = body;
+      Some(section.line_number)
+    }
+    None => {
+      let last_section = doc.last_section_mut();
+      trim_trailing_empty_lines(last_section);
+      last_section.push_line("");
+      let line_number = doc.lines_count() + 1;
+      let mut section = Section::new(line_number, section_title, vec![]);
+      section.body = body;
+      doc.content_sections.push(section);
+      Some(line_number)
+    }
+  }
+}
+
+/// indicates whether the given line contains a link with the given target
+fn points_to(line: &Line, target: &str) -> bool {
+  let mut links = vec![];
+  let mut images = vec![];
+  Markdown::new([(0, line.text.as_str())]).references(&mut links, &mut images);
+  links.iter().any(|link| link.points_to(target))
+}
+
+/// provides the human-readable part of the given section title
+fn human_title(section_title: &str) -> &str {
+  let (_level, start) = Section::parse_title(section_title);
+  &section_title[start..]
+}
+
+/// removes all empty lines at the end of the given section
+fn trim_trailing_empty_lines(section: &mut Section) {
+  while section.body.last().is_some_and(|line| line.text.is_empty()) {
+    section.body.pop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod entries {
+    use crate::{Tikibase, test};
+    use indoc::indoc;
+
+    #[test]
+    fn ignores_generated_sections() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", "# One\n\n[Two](2.md)\n[Three](3.md)\n", dir.path());
+      let content = indoc! {"
+        # Two
+
+        text
+
+        ### backlinks
+
+        - [One](1.md)
+        - [Three](3.md)
+        "};
+      test::create_file("2.md", content, dir.path());
+      test::create_file("3.md", "# Three\n\ntext\n", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = super::super::entries(&base, "2.md", "### backlinks").unwrap();
+      assert_eq!(have, vec!["- [One](1.md)"]);
+      let have = super::super::entries(&base, "3.md", "### backlinks").unwrap();
+      assert_eq!(have, vec!["- [One](1.md)"]);
+    }
+
+    #[test]
+    fn sorted_and_deduplicated() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = indoc! {"
+        # One
+
+        [Four](4.md)
+
+        ### backlinks
+
+        - [Old](old.md)
+        "};
+      test::create_file("1.md", content, dir.path());
+      test::create_file(
+        "2.md",
+        "# Two\n[One](1.md)\n[One again](1.md#one)",
+        dir.path(),
+      );
+      test::create_file("sub/3.md", "# Three\n[One](../1.md)", dir.path());
+      test::create_file("4.md", "# Four\n[One](1.md)", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = super::super::entries(&base, "1.md", "### backlinks").unwrap();
+      let want = vec!["- [Two](2.md)", "- [Three](sub/3.md)"];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn custom_format() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "tikibase.json",
+        r#"{ "backlinksFormat": "* {path}: {title}" }"#,
+        dir.path(),
+      );
+      test::create_file("1.md", "# One\ntext", dir.path());
+      test::create_file("2.md", "# Two\n[One](1.md)", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      let have = super::super::entries(&base, "1.md", "### backlinks").unwrap();
+      assert_eq!(have, vec!["* 2.md: Two"]);
+    }
+  }
+
+  mod regenerate {
+    use crate::check::Location;
+    use crate::fix::Result::Unfixable;
+    use crate::{Tikibase, test};
+    use big_s::S;
+
+    #[test]
+    fn no_entries() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "tikibase.json",
+        "{ \"backlinksSection\": \"### backlinks\" }",
+        dir.path(),
+      );
+      let content = "# One\n\n[Two](2.md)\n";
+      test::create_file("1.md", content, dir.path());
+      test::create_file("2.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let location = Location {
+        file: S("1.md"),
+        line: 3,
+        start: 0,
+        end: 0,
+      };
+      let Unfixable = super::super::regenerate(&mut base, location, S("2.md"), "### backlinks")
+      else {
+        panic!("fixed");
+      };
+      pretty::assert_eq!(test::load_file("1.md", dir.path()), content);
+    }
+  }
+
+  mod remove_entry {
+    use crate::check::Location;
+    use crate::fix::Fix::RemovedStaleBacklink;
+    use crate::fix::Result::Fixed;
+    use crate::{Tikibase, test};
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn removes_only_the_given_entry() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file(
+        "tikibase.json",
+        "{ \"backlinksSection\": \"### backlinks\" }",
+        dir.path(),
+      );
+      let content = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Two](2.md)
+        - [Old](old.md)
+        "};
+      test::create_file("1.md", content, dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let location = Location {
+        file: S("1.md"),
+        line: 6,
+        start: 2,
+        end: 15,
+      };
+      let Fixed(have) = super::super::remove_entry(&mut base, location.clone(), S("old.md")) else {
+        panic!("not fixed");
+      };
+      let want = RemovedStaleBacklink {
+        location,
+        path: S("old.md"),
+      };
+      pretty::assert_eq!(have, want);
+      let want = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Two](2.md)
+        "};
+      pretty::assert_eq!(test::load_file("1.md", dir.path()), want);
+    }
+    #[test]
+    fn occurrences_section() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("tikibase.json", "{ \"bidiLinks\": true }", dir.path());
+      let content = indoc! {"
+        # One
+        text
+
+        ### occurrences
+
+        - [Two](2.md)
+        - [Old](old.md)
+        "};
+      test::create_file("1.md", content, dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let location = Location {
+        file: S("1.md"),
+        line: 6,
+        start: 2,
+        end: 15,
+      };
+      let Fixed(_) = super::super::remove_entry(&mut base, location, S("old.md")) else {
+        panic!("not fixed");
+      };
+      let want = indoc! {"
+        # One
+        text
+
+        ### occurrences
+
+        - [Two](2.md)
+        "};
+      pretty::assert_eq!(test::load_file("1.md", dir.path()), want);
+    }
+  }
+
+  mod update_section {
+    use crate::database::Document;
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn append() {
+      let give = indoc! {"
+        # One
+        text
+
+
+        "};
+      let mut doc = Document::from_str("1.md", give).unwrap();
+      let have = super::super::update_section(&mut doc, "### backlinks", vec![S("- [Two](2.md)")]);
+      assert_eq!(have, Some(3));
+      let want = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Two](2.md)
+        "};
+      pretty::assert_eq!(doc.text(), want);
+    }
+
+    #[test]
+    fn replace() {
+      let give = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Old](old.md)
+
+        ### other
+        text
+        "};
+      let mut doc = Document::from_str("1.md", give).unwrap();
+      let have = super::super::update_section(&mut doc, "### backlinks", vec![S("- [Two](2.md)")]);
+      assert_eq!(have, Some(3));
+      let want = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Two](2.md)
+
+        ### other
+        text
+        "};
+      pretty::assert_eq!(doc.text(), want);
+    }
+
+    #[test]
+    fn remove() {
+      let give = indoc! {"
+        # One
+        text
+
+        ### backlinks
+
+        - [Old](old.md)
+        "};
+      let mut doc = Document::from_str("1.md", give).unwrap();
+      let have = super::super::update_section(&mut doc, "### backlinks", vec![]);
+      assert_eq!(have, None);
+      pretty::assert_eq!(doc.text(), "# One\ntext\n");
+    }
+  }
+}
@@ -0,0 +1,426 @@
+use super::Fix::MergedDuplicateSections;
+use crate::check::Location;
+use crate::config::SlugStyle;
+use crate::database::{Document, Image, Line, Link, Markdown, Section};
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{Tikibase, fix};
+
+/// merges the bodies of all sections with the given title in the document at the given location
+/// into the first of these sections and updates the links to the anchors of the merged sections
+pub fn merge_sections(base: &mut Tikibase, location: Location, title: String) -> fix::Result {
+  let slug_style = base.dir.config.slug_style();
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let old_anchors = section_anchors(doc, slug_style);
+  let positions: Vec<usize> = doc
+    .sections()
+    .enumerate()
+    .filter(|(_, section)| section.human_title() == title)
+    .map(|(i, _)| i)
+    .collect();
+  let [first, duplicates @ ..] = positions.as_slice() else {
+    return Unfixable;
+  };
+  if duplicates.is_empty() {
+    return Unfixable;
+  }
+  // the title section comes first, so duplicates are always content sections
+  let mut removed: Vec<Section> = duplicates
+    .iter()
+    .rev()
+    .map(|position| doc.content_sections.remove(position - 1))
+    .collect();
+  removed.reverse();
+  let is_last = *first == doc.content_sections.len();
+  let section = if *first == 0 {
+    &mut doc.title_section
+  } else {
+    &mut doc.content_sections[first - 1]
+  };
+  merge_bodies(section, removed, is_last);
+  doc.refresh();
+  let renamed = renamed_anchors(
+    &old_anchors,
+    &section_anchors(doc, slug_style),
+    *first,
+    duplicates,
+  );
+  let section = doc.sections().nth(*first).unwrap();
+  let merged_location = Location {
+    file: location.file.clone(),
+    line: section.line_number,
+    start: section.title_text_start as u32,
+    end: section.title_text_end(),
+  };
+  // links within the document
+  for link in doc.links.clone() {
+    if let Some((_, new_anchor)) = renamed.iter().find(|(old, _)| old == &link.target) {
+      retarget(
+        doc,
+        link.line,
+        link.start,
+        link.end,
+        &link.target,
+        new_anchor,
+      );
+    }
+  }
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  // the preview of this fix shows only the changes to the merged document
+  if !base.preview {
+    let mut changed_docs: Vec<String> = vec![];
+    for backlink in base.backlinks().to(&location.file).to_vec() {
+      let Some((file, anchor)) = backlink.target.split_once('#') else {
+        continue;
+      };
+      let anchor = format!("#{anchor}");
+      let Some((_, new_anchor)) = renamed.iter().find(|(old, _)| old == &anchor) else {
+        continue;
+      };
+      let Some(source_doc) = base.get_doc_mut(&backlink.source) else {
+        continue;
+      };
+      let new_target = format!("{file}{new_anchor}");
+      if retarget(
+        source_doc,
+        backlink.line,
+        backlink.start,
+        backlink.end,
+        &backlink.target,
+        &new_target,
+      ) && !changed_docs.contains(&backlink.source)
+      {
+        changed_docs.push(backlink.source);
+      }
+    }
+    for changed_doc in changed_docs {
+      if let Err(issue) = base.save_doc(&changed_doc) {
+        return Failed(issue);
+      }
+    }
+  }
+  Fixed(MergedDuplicateSections {
+    location: merged_location,
+    title,
+    count: positions.len(),
+  })
+}
+
+/// appends the bodies of the given duplicate sections to the body of the given section,
+/// separated by a single empty line, without the lines that the merged body already contains
+fn merge_bodies(section: &mut Section, duplicates: Vec<Section>, is_last: bool) {
+  let ends_with_empty_line = section
+    .body
+    .last()
+    .is_some_and(|line| line.text.trim().is_empty());
+  // the merged content keeps the empty lines between the title and the content of the first section
+  let title_empty_lines = section
+    .body
+    .iter()
+    .take_while(|line| line.text.trim().is_empty())
+    .count();
+  let mut pieces = vec![core::mem::take(&mut section.body)];
+  pieces.extend(duplicates.into_iter().map(|duplicate| duplicate.body));
+  for (i, piece) in pieces.into_iter().enumerate() {
+    let mut piece = if i == 0 {
+      piece
+    } else {
+      without_duplicate_lines(piece, &section.body)
+    };
+    while piece.last().is_some_and(|line| line.text.trim().is_empty()) {
+      piece.pop();
+    }
+    let leading_empty_lines = piece
+      .iter()
+      .take_while(|line| line.text.trim().is_empty())
+      .count();
+    if piece.len() == leading_empty_lines {
+      continue;
+    }
+    let separator_lines = if section.body.is_empty() {
+      title_empty_lines
+    } else {
+      1
+    };
+    for _ in 0..separator_lines {
+      section.push_line("");
+    }
+    section
+      .body
+      .extend(piece.into_iter().skip(leading_empty_lines));
+  }
+  if ends_with_empty_line && !is_last {
+    section.push_line("");
+  }
+}
+
+/// provides the given lines without the lines containing links or images that the given existing lines contain,
+/// removes the empty lines that become redundant through this
+fn without_duplicate_lines(lines: Vec<Line>, existing: &[Line]) -> Vec<Line> {
+  let mut links = vec![];
+  let mut images = vec![];
+  Markdown::new(
+    lines
+      .iter()
+      .enumerate()
+      .map(|(i, line)| (i as u32, line.text.as_str())),
+  )
+  .references(&mut links, &mut images);
+  let mut result: Vec<Line> = vec![];
+  let mut removed_line = false;
+  for (i, line) in lines.into_iter().enumerate() {
+    let i = i as u32;
+    if line.text.trim().is_empty() {
+      if removed_line && result.last().is_none_or(|last| last.text.trim().is_empty()) {
+        continue;
+      }
+    } else if is_link_line(&line, i, &links, &images)
+      && existing.iter().any(|existing| existing.text == line.text)
+    {
+      removed_line = true;
+      continue;
+    } else {
+      removed_line = false;
+    }
+    result.push(line);
+  }
+  result
+}
+
+/// indicates whether the given line, which has the given index, contains one of the given links or images
+/// and isn't a table row
+fn is_link_line(line: &Line, index: u32, links: &[Link], images: &[Image]) -> bool {
+  !line.text.trim_start().starts_with('|')
+    && (links.iter().any(|link| link.line == index)
+      || images.iter().any(|image| image.line == index))
+}
+
+/// provides the old and new anchors of the sections whose anchor changed through merging,
+/// given the anchors of the sections before and after merging the given duplicates into the given first section
+fn renamed_anchors(
+  old_anchors: &[String],
+  new_anchors: &[String],
+  first: usize,
+  duplicates: &[usize],
+) -> Vec<(String, String)> {
+  let mut result = vec![];
+  let mut survivors = 0;
+  for (i, old_anchor) in old_anchors.iter().enumerate() {
+    let new_anchor = if duplicates.contains(&i) {
+      &new_anchors[first]
+    } else {
+      survivors += 1;
+      &new_anchors[survivors - 1]
+    };
+    if old_anchor != new_anchor {
+      result.push((old_anchor.clone(), new_anchor.clone()));
+    }
+  }
+  result
+}
+
+/// replaces the given link target inside the given span of the given document with the given new target,
+/// indicates whether it did
+fn retarget(
+  doc: &mut Document,
+  line: u32,
+  start: u32,
+  end: u32,
+  target: &str,
+  new_target: &str,
+) -> bool {
+  let Some(line) = doc.line_mut(line) else {
+    return false;
+  };
+  let start = start as usize;
+  let end = (end as usize).min(line.text.len());
+  let Some(target_start) = line
+    .text
+    .get(start..end)
+    .and_then(|span| span.find(target))
+    .map(|pos| start + pos)
+  else {
+    return false;
+  };
+  line
+    .text
+    .replace_range(target_start..target_start + target.len(), new_target);
+  true
+}
+
+/// provides the anchors of the sections of the given document
+fn section_anchors(doc: &Document, style: SlugStyle) -> Vec<String> {
+  let mut result = doc.anchors(style);
+  result.truncate(doc.sections().count());
+  result
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod merge_bodies {
+    use super::super::merge_bodies;
+    use crate::database::Section;
+
+    #[test]
+    fn separates_bodies_with_empty_line() {
+      let mut section = Section::new(2, "### links", vec!["- [One](1.md)", ""]);
+      let duplicates = vec![
+        Section::new(5, "### links", vec!["", "- [Two](2.md)", "", ""]),
+        Section::new(9, "### links", vec![""]),
+        Section::new(11, "### links", vec!["- [Three](3.md)", ""]),
+      ];
+      merge_bodies(&mut section, duplicates, false);
+      let want = "### links\n- [One](1.md)\n\n- [Two](2.md)\n\n- [Three](3.md)\n\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn last_section() {
+      let mut section = Section::new(2, "### links", vec!["- [One](1.md)", ""]);
+      let duplicates = vec![Section::new(5, "### links", vec!["- [Two](2.md)"])];
+      merge_bodies(&mut section, duplicates, true);
+      let want = "### links\n- [One](1.md)\n\n- [Two](2.md)\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn keeps_empty_lines_in_code_blocks() {
+      let mut section = Section::new(2, "### code", vec!["```", "a", "", "", "b", "```"]);
+      let duplicates = vec![Section::new(9, "### code", vec!["```", "c", "", "", "```"])];
+      merge_bodies(&mut section, duplicates, true);
+      let want = "### code\n```\na\n\n\nb\n```\n\n```\nc\n\n\n```\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn removes_duplicate_lines() {
+      let mut section = Section::new(2, "### links", vec!["- [A](a.md)", "- [B](b.md)", ""]);
+      let duplicates = vec![Section::new(
+        6,
+        "### links",
+        vec!["- [B](b.md)", "", "- [C](c.md)", "- [A](a.md)"],
+      )];
+      merge_bodies(&mut section, duplicates, true);
+      let want = "### links\n- [A](a.md)\n- [B](b.md)\n\n- [C](c.md)\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn keeps_duplicate_lines_without_links() {
+      let mut section = Section::new(2, "### notes", vec!["- TODO", "", "---", "same text", ""]);
+      let duplicates = vec![Section::new(
+        8,
+        "### notes",
+        vec!["- TODO", "", "---", "same text"],
+      )];
+      merge_bodies(&mut section, duplicates, true);
+      let want = "### notes\n- TODO\n\n---\nsame text\n\n- TODO\n\n---\nsame text\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn keeps_tables() {
+      let mut section = Section::new(
+        2,
+        "### data",
+        vec!["| A | B |", "|---|---|", "| [one](1.md) | 1 |", ""],
+      );
+      let duplicates = vec![Section::new(
+        7,
+        "### data",
+        vec!["| A | B |", "|---|---|", "| [one](1.md) | 1 |"],
+      )];
+      merge_bodies(&mut section, duplicates, true);
+      let want = "### data\n| A | B |\n|---|---|\n| [one](1.md) | 1 |\n\n| A | B |\n|---|---|\n| [one](1.md) | 1 |\n";
+      assert_eq!(section.text(), want);
+    }
+
+    #[test]
+    fn keeps_empty_lines_after_title() {
+      let mut section = Section::new(2, "### notes", vec![""]);
+      let duplicates = vec![
+        Section::new(4, "### notes", vec!["", "text", ""]),
+        Section::new(8, "### notes", vec!["", "more text", ""]),
+      ];
+      merge_bodies(&mut section, duplicates, false);
+      assert_eq!(section.text(), "### notes\n\ntext\n\nmore text\n\n");
+    }
+
+    #[test]
+    fn only_duplicate_lines() {
+      let mut section = Section::new(2, "### links", vec!["- [A](a.md)", ""]);
+      let duplicates = vec![Section::new(5, "### links", vec!["- [A](a.md)", ""])];
+      merge_bodies(&mut section, duplicates, false);
+      assert_eq!(section.text(), "### links\n- [A](a.md)\n\n");
+    }
+  }
+
+  mod merge_sections {
+    use crate::check::Location;
+    use crate::fix::Fix::MergedDuplicateSections;
+    use crate::fix::Result::Fixed;
+    use crate::{Tikibase, test};
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn updates_anchors() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = indoc! {"
+        # One
+
+        ### notes
+        first
+
+        ### links
+        [second notes](#notes-1)
+
+        ### notes
+        second
+        "};
+      test::create_file("1.md", content, dir.path());
+      test::create_file("2.md", "# Two\n\n[One](1.md#notes-1)\n", dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let location = Location {
+        file: S("1.md"),
+        line: 8,
+        start: 4,
+        end: 9,
+      };
+      let Fixed(have) = super::super::merge_sections(&mut base, location, S("notes")) else {
+        panic!("not fixed");
+      };
+      let want = MergedDuplicateSections {
+        location: Location {
+          file: S("1.md"),
+          line: 2,
+          start: 4,
+          end: 9,
+        },
+        title: S("notes"),
+        count: 2,
+      };
+      pretty::assert_eq!(have, want);
+      let want = indoc! {"
+        # One
+
+        ### notes
+        first
+
+        second
+
+        ### links
+        [second notes](#notes)
+        "};
+      pretty::assert_eq!(test::load_file("1.md", dir.path()), want);
+      pretty::assert_eq!(
+        test::load_file("2.md", dir.path()),
+        "# Two\n\n[One](1.md#notes)\n"
+      );
+    }
+  }
+}
@@ -1,14 +1,15 @@
 use super::Fix::RemovedEmptySection;
 use crate::check::Location;
-use crate::fix::Result::Fixed;
+use crate::fix::Result::{Failed, Fixed};
 use crate::{Tikibase, fix};
 
 pub fn remove_section(base: &mut Tikibase, title: String, location: Location) -> fix::Result {
-  let base_dir = base.root.clone();
   let doc = base.get_doc_mut(&location.file).unwrap();
   doc
     .content_sections
     .retain(|section| section.human_title() != title);
-  doc.save(&base_dir);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(RemovedEmptySection { title, location })
 }
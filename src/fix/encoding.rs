@@ -0,0 +1,48 @@
+use super::Fix::ConvertedToUtf8;
+use crate::check::Location;
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{Tikibase, fix};
+
+/// saves the document at the given location, which was read as Windows-1252, as UTF-8
+pub fn convert_to_utf8(base: &mut Tikibase, location: Location) -> fix::Result {
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  doc.non_utf8_line = None;
+  doc.format.windows1252 = false;
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(ConvertedToUtf8 { location })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check::Location;
+  use crate::fix::Fix::ConvertedToUtf8;
+  use crate::fix::Result::Fixed;
+  use crate::{Tikibase, test};
+  use big_s::S;
+  use fs_err as fs;
+
+  #[test]
+  fn convert_to_utf8() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("1.md"), b"# One\n\ncaf\xe9\n").unwrap();
+    let mut base = Tikibase::load(dir.path()).unwrap();
+    let location = Location {
+      file: S("1.md"),
+      line: 2,
+      start: 0,
+      end: 0,
+    };
+    let Fixed(have) = super::convert_to_utf8(&mut base, location.clone()) else {
+      panic!("not fixed");
+    };
+    pretty::assert_eq!(have, ConvertedToUtf8 { location });
+    assert_eq!(test::load_file("1.md", dir.path()), "# One\n\ncaf\u{e9}\n");
+    let doc = base.get_doc("1.md").unwrap();
+    assert_eq!(doc.non_utf8_line, None);
+    assert!(!doc.format.windows1252);
+  }
+}
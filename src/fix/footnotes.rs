@@ -0,0 +1,275 @@
+use super::Fix::{MovedFootnoteDefinitions, RemovedUnusedFootnote, RenumberedFootnotes};
+use crate::check::Location;
+use crate::database::{Document, Section};
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{Tikibase, fix};
+use core::ops::RangeInclusive;
+
+/// moves all footnote definitions of the document at the given location to its end,
+/// before the sections that Tikibase generates, in the order of their first reference
+pub fn move_definitions(base: &mut Tikibase, location: Location) -> fix::Result {
+  let backlinks_section = base.dir.config.backlinks_section.clone();
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let footnotes = match doc.footnotes() {
+    Ok(footnotes) => footnotes,
+    Err(issue) => return Failed(issue),
+  };
+  let blocks = doc.footnote_definition_blocks(&footnotes);
+  let lines: Vec<String> = doc.lines().map(|line| line.text.clone()).collect();
+  remove_blocks(doc, &blocks, &lines);
+  let generated = doc.generated_sections_at_end(backlinks_section.as_deref());
+  let section = match doc.content_sections.len() - generated {
+    0 => &mut doc.title_section,
+    count => &mut doc.content_sections[count - 1],
+  };
+  trim_trailing_empty_lines(section);
+  section.push_line("");
+  for block in blocks {
+    for line in block {
+      section.push_line(lines[line as usize].clone());
+    }
+  }
+  if generated > 0 {
+    // separate the definitions from the generated sections
+    section.push_line("");
+  }
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(MovedFootnoteDefinitions { location })
+}
+
+/// removes the unused footnote definition at the given location
+pub fn remove_unused(base: &mut Tikibase, location: Location, identifier: String) -> fix::Result {
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let footnotes = match doc.footnotes() {
+    Ok(footnotes) => footnotes,
+    Err(issue) => return Failed(issue),
+  };
+  let Some(block) = doc
+    .footnote_definition_blocks(&footnotes)
+    .into_iter()
+    .find(|block| *block.start() == location.line)
+  else {
+    // the definition doesn't start its line
+    return Unfixable;
+  };
+  let lines: Vec<String> = doc.lines().map(|line| line.text.clone()).collect();
+  remove_blocks(doc, &[block], &lines);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(RemovedUnusedFootnote {
+    location,
+    identifier,
+  })
+}
+
+/// numbers the numeric footnotes of the document at the given location in the order of their first reference
+pub fn renumber(base: &mut Tikibase, location: Location) -> fix::Result {
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let footnotes = match doc.footnotes() {
+    Ok(footnotes) => footnotes,
+    Err(issue) => return Failed(issue),
+  };
+  let Some(renumbering) = footnotes.renumbering() else {
+    return Unfixable;
+  };
+  let mut occurrences: Vec<_> = footnotes
+    .references
+    .iter()
+    .chain(&footnotes.definitions)
+    .collect();
+  // replace from the end so that the positions of the remaining footnotes stay valid
+  occurrences.sort_by_key(|footnote| (footnote.line, footnote.start));
+  for footnote in occurrences.into_iter().rev() {
+    let Some((old, new)) = renumbering
+      .iter()
+      .find(|(old, _new)| old == &footnote.identifier)
+    else {
+      continue;
+    };
+    let Some(line) = doc.line_mut(footnote.line) else {
+      continue;
+    };
+    let start = footnote.start as usize;
+    let end = start + old.len() + 3;
+    line.text.replace_range(start..end, &format!("[^{new}]"));
+  }
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(RenumberedFootnotes { location })
+}
+
+/// removes the given blocks of lines from the given document,
+/// together with the empty line before a block if an empty line also follows it,
+/// and the empty lines at the end of the document.
+/// The given lines are the current lines of the document.
+fn remove_blocks(doc: &mut Document, blocks: &[RangeInclusive<u32>], lines: &[String]) {
+  let is_empty = |line: u32| {
+    lines
+      .get(line as usize)
+      .is_some_and(|text| text.trim().is_empty())
+  };
+  let mut removed: Vec<u32> = vec![];
+  for block in blocks {
+    removed.extend(block.clone());
+    if *block.start() > 0 && is_empty(block.start() - 1) && is_empty(block.end() + 1) {
+      removed.push(block.start() - 1);
+    }
+  }
+  removed.sort_unstable();
+  removed.dedup();
+  for line in removed.into_iter().rev() {
+    doc.remove_line(line);
+  }
+  trim_trailing_empty_lines(doc.last_section_mut());
+}
+
+/// removes the empty lines at the end of the given section
+fn trim_trailing_empty_lines(section: &mut Section) {
+  while section
+    .body
+    .last()
+    .is_some_and(|line| line.text.trim().is_empty())
+  {
+    section.body.pop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::check::Location;
+  use crate::database::Tikibase;
+  use crate::fix::Fix::{MovedFootnoteDefinitions, RemovedUnusedFootnote, RenumberedFootnotes};
+  use crate::fix::Result::Fixed;
+  use crate::test;
+  use big_s::S;
+  use indoc::indoc;
+
+  #[test]
+  fn move_definitions() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {"
+      # Title
+
+      text[^b] and[^a]
+
+      [^a]: definition a
+        continued
+
+      ### section
+
+      more text
+
+      [^b]: definition b
+      "};
+    test::create_file("1.md", content, dir.path());
+    let mut base = Tikibase::load(dir.path()).unwrap();
+    let location = Location {
+      file: S("1.md"),
+      line: 4,
+      start: 0,
+      end: 5,
+    };
+    let Fixed(have) = super::move_definitions(&mut base, location.clone()) else {
+      panic!()
+    };
+    assert_eq!(have, MovedFootnoteDefinitions { location });
+    let want = indoc! {"
+      # Title
+
+      text[^b] and[^a]
+
+      ### section
+
+      more text
+
+      [^b]: definition b
+      [^a]: definition a
+        continued
+      "};
+    assert_eq!(test::load_file("1.md", dir.path()), want);
+  }
+
+  #[test]
+  fn remove_unused() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {"
+      # Title
+
+      text[^used]
+
+      [^used]: used
+      [^unused]: unused
+        continued
+      "};
+    test::create_file("1.md", content, dir.path());
+    let mut base = Tikibase::load(dir.path()).unwrap();
+    let location = Location {
+      file: S("1.md"),
+      line: 5,
+      start: 0,
+      end: 11,
+    };
+    let Fixed(have) = super::remove_unused(&mut base, location.clone(), S("unused")) else {
+      panic!()
+    };
+    let want = RemovedUnusedFootnote {
+      location,
+      identifier: S("unused"),
+    };
+    assert_eq!(have, want);
+    let want = indoc! {"
+      # Title
+
+      text[^used]
+
+      [^used]: used
+      "};
+    assert_eq!(test::load_file("1.md", dir.path()), want);
+  }
+
+  #[test]
+  fn renumber() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let content = indoc! {"
+      # Title
+
+      one[^2] two[^1] three[^named] again[^2]
+
+      [^1]: first
+      [^2]: second
+      [^named]: named
+      "};
+    test::create_file("1.md", content, dir.path());
+    let mut base = Tikibase::load(dir.path()).unwrap();
+    let location = Location {
+      file: S("1.md"),
+      line: 2,
+      start: 3,
+      end: 7,
+    };
+    let Fixed(have) = super::renumber(&mut base, location.clone()) else {
+      panic!()
+    };
+    assert_eq!(have, RenumberedFootnotes { location });
+    let want = indoc! {"
+      # Title
+
+      one[^1] two[^2] three[^named] again[^1]
+
+      [^2]: first
+      [^1]: second
+      [^named]: named
+      "};
+    assert_eq!(test::load_file("1.md", dir.path()), want);
+  }
+}
@@ -2,7 +2,7 @@ use super::Fix::NormalizedSectionLevel;
 use crate::check::Location;
 use crate::database::Tikibase;
 use crate::fix;
-use crate::fix::Result::Fixed;
+use crate::fix::Result::{Failed, Fixed};
 
 pub fn normalize_outliers(
   base: &mut Tikibase,
@@ -11,13 +11,14 @@ pub fn normalize_outliers(
   old_level: u8,
   new_level: u8,
 ) -> fix::Result {
-  let base_dir = base.root.clone();
   let doc = base.get_doc_mut(&location.file).unwrap();
   let section = doc
     .section_with_human_title_mut(&section_human_title)
     .unwrap();
-  section.title_line.text = title_at_level(&section_human_title, new_level as usize);
-  doc.save(&base_dir);
+  section.set_title(title_at_level(&section_human_title, new_level as usize));
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(NormalizedSectionLevel {
     location,
     section_human_title,
@@ -34,11 +35,12 @@ pub fn set_to_configured_section_level(
   configured_level: u8,
   configured_title: String,
 ) -> fix::Result {
-  let base_dir = base.root.clone();
   let doc = base.get_doc_mut(&location.file).unwrap();
   let section = doc.section_with_title_mut(&actual_title).unwrap();
-  section.title_line.text = configured_title;
-  doc.save(&base_dir);
+  section.set_title(configured_title.as_str());
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(NormalizedSectionLevel {
     location,
     section_human_title: actual_title,
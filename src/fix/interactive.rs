@@ -0,0 +1,300 @@
+use crate::check::Issue;
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{Message, Tikibase, fix};
+use camino::Utf8Path;
+use core::mem::{Discriminant, discriminant};
+use std::io::{self, BufRead, Write};
+use std::{env, process};
+
+/// the number of unchanged lines shown around a proposed change
+const CONTEXT_LINES: usize = 2;
+
+/// what the user wants to do with a proposed fix
+#[derive(Debug, Eq, PartialEq)]
+pub enum Answer {
+  /// apply this fix
+  Accept,
+  /// apply this fix and all further fixes for issues of the same kind
+  AcceptAll,
+  /// don't apply this fix, let the user edit the file instead
+  Edit,
+  /// don't apply this fix
+  Skip,
+}
+
+/// asks the user which of the proposed fixes to apply
+pub trait Prompt {
+  /// asks what to do about the given issue, given the preview of its fix
+  fn ask(&mut self, issue: &Message, preview: &str) -> Answer;
+
+  /// lets the user edit the given file, starting at the given line (0-based)
+  fn edit(&mut self, file: &Utf8Path, line: u32);
+}
+
+/// asks the user via the terminal,
+/// writes to stderr so that the prompts don't mix with the output on stdout
+pub struct Terminal;
+
+impl Prompt for Terminal {
+  fn ask(&mut self, issue: &Message, preview: &str) -> Answer {
+    eprintln!("\n{}\n{preview}", issue.to_text());
+    loop {
+      eprint!("apply this fix? [y]es, [n]o, [e]dit, [a]ll of this kind: ");
+      let _ = io::stderr().flush();
+      let mut input = String::new();
+      match io::stdin().lock().read_line(&mut input) {
+        Ok(0) | Err(_) => return Answer::Skip,
+        Ok(_) => {}
+      }
+      match input.trim() {
+        "y" => return Answer::Accept,
+        "n" => return Answer::Skip,
+        "e" => return Answer::Edit,
+        "a" => return Answer::AcceptAll,
+        _ => {}
+      }
+    }
+  }
+
+  fn edit(&mut self, file: &Utf8Path, line: u32) {
+    let editor = env::var("EDITOR").unwrap_or_else(|_| "vi".into());
+    let result = process::Command::new(editor)
+      .arg(format!("+{}", line + 1))
+      .arg(file)
+      // the editor draws on the terminal even if stdout gets redirected
+      .stdout(io::stderr())
+      .status();
+    if let Err(err) = result {
+      eprintln!("cannot start the editor: {err}");
+    }
+  }
+}
+
+/// the decisions the user made so far
+#[derive(Default)]
+pub struct Decisions {
+  /// the kinds of issues that get fixed without asking
+  accepted_kinds: Vec<Discriminant<Issue>>,
+  /// the issues that the user doesn't want to fix automatically
+  skipped: Vec<Issue>,
+}
+
+/// fixes the given issue if the user approves the fix
+pub fn fix<P: Prompt>(
+  issue: Issue,
+  base: &mut Tikibase,
+  prompt: &mut P,
+  decisions: &mut Decisions,
+) -> fix::Result {
+  if decisions.skipped.contains(&issue) {
+    return Unfixable;
+  }
+  if decisions.accepted_kinds.contains(&discriminant(&issue)) {
+    return fix::fix(issue, base);
+  }
+  let Some(location) = issue.location().cloned() else {
+    return fix::fix(issue, base);
+  };
+  let Some(original) = base.get_doc(&location.file).cloned() else {
+    return fix::fix(issue, base);
+  };
+  base.preview = true;
+  let result = fix::fix(issue.clone(), base);
+  base.preview = false;
+  let fixed_text = base
+    .get_doc(&location.file)
+    .map(|doc| doc.text())
+    .unwrap_or_default();
+  let original_text = original.text();
+  base.replace_doc(original);
+  match result {
+    Fixed(_) => {}
+    Failed(_) | Unfixable => return result,
+  }
+  let message = Message::from_issue(issue.clone());
+  match prompt.ask(&message, &preview(&original_text, &fixed_text)) {
+    Answer::Accept => fix::fix(issue, base),
+    Answer::AcceptAll => {
+      decisions.accepted_kinds.push(discriminant(&issue));
+      fix::fix(issue, base)
+    }
+    Answer::Edit => {
+      prompt.edit(&base.root.join(&location.file), location.line);
+      decisions.skipped.push(issue);
+      match base.reload_doc(&location.file) {
+        Ok(()) => Unfixable,
+        Err(problem) => Failed(problem),
+      }
+    }
+    Answer::Skip => {
+      decisions.skipped.push(issue);
+      Unfixable
+    }
+  }
+}
+
+/// describes the difference between the given texts:
+/// the changed lines with a few unchanged lines around them
+fn preview(before: &str, after: &str) -> String {
+  let before: Vec<&str> = before.lines().collect();
+  let after: Vec<&str> = after.lines().collect();
+  let prefix = before
+    .iter()
+    .zip(&after)
+    .take_while(|(old, new)| old == new)
+    .count();
+  let suffix = before[prefix..]
+    .iter()
+    .rev()
+    .zip(after[prefix..].iter().rev())
+    .take_while(|(old, new)| old == new)
+    .count();
+  if prefix == before.len() && prefix == after.len() {
+    return "  (the text doesn't change)\n".into();
+  }
+  let mut result = String::new();
+  let context_start = prefix.saturating_sub(CONTEXT_LINES);
+  for (i, line) in before.iter().enumerate().take(prefix).skip(context_start) {
+    result.push_str(&format!("{:>4}   {line}\n", i + 1));
+  }
+  for (i, line) in before
+    .iter()
+    .enumerate()
+    .take(before.len() - suffix)
+    .skip(prefix)
+  {
+    result.push_str(&format!("{:>4} - {line}\n", i + 1));
+  }
+  for line in &after[prefix..after.len() - suffix] {
+    result.push_str(&format!("     + {line}\n"));
+  }
+  let context_end = (before.len() - suffix + CONTEXT_LINES).min(before.len());
+  for (i, line) in before
+    .iter()
+    .enumerate()
+    .take(context_end)
+    .skip(before.len() - suffix)
+  {
+    result.push_str(&format!("{:>4}   {line}\n", i + 1));
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod fix {
+    use super::super::{Answer, Decisions, Prompt, fix};
+    use crate::fix::Result::{Fixed, Unfixable};
+    use crate::{Message, Tikibase, commands, test};
+    use camino::Utf8Path;
+
+    /// answers with the given answers, in order
+    struct Scripted {
+      answers: Vec<Answer>,
+      previews: Vec<String>,
+    }
+
+    impl Prompt for Scripted {
+      fn ask(&mut self, _issue: &Message, preview: &str) -> Answer {
+        self.previews.push(preview.into());
+        self.answers.remove(0)
+      }
+
+      fn edit(&mut self, _file: &Utf8Path, _line: u32) {}
+    }
+
+    const CONTENT: &str = "# One\n\n### empty 1\n\n### empty 2\n\n### text\n\n[Two](2.md)\n";
+
+    fn setup() -> (camino_tempfile::Utf8TempDir, Tikibase) {
+      let dir = camino_tempfile::tempdir().unwrap();
+      test::create_file("1.md", CONTENT, dir.path());
+      test::create_file("2.md", "# Two\n\n[One](1.md)\n", dir.path());
+      let base = Tikibase::load(dir.path()).unwrap();
+      (dir, base)
+    }
+
+    #[test]
+    fn accept() {
+      let (dir, mut base) = setup();
+      let mut prompt = Scripted {
+        answers: vec![Answer::Accept],
+        previews: vec![],
+      };
+      let issue = commands::check(&base, false).issues.remove(0);
+      let mut decisions = Decisions::default();
+      let Fixed(_) = fix(issue, &mut base, &mut prompt, &mut decisions) else {
+        panic!()
+      };
+      let want = "# One\n\n### empty 2\n\n### text\n\n[Two](2.md)\n";
+      assert_eq!(test::load_file("1.md", dir.path()), want);
+      let want =
+        "   1   # One\n   2   \n   3 - ### empty 1\n   4 - \n   5   ### empty 2\n   6   \n";
+      assert_eq!(prompt.previews, vec![want]);
+    }
+
+    #[test]
+    fn skip() {
+      let (dir, mut base) = setup();
+      let mut prompt = Scripted {
+        answers: vec![Answer::Skip],
+        previews: vec![],
+      };
+      let issue = commands::check(&base, false).issues.remove(0);
+      let mut decisions = Decisions::default();
+      let Unfixable = fix(issue.clone(), &mut base, &mut prompt, &mut decisions) else {
+        panic!()
+      };
+      assert_eq!(test::load_file("1.md", dir.path()), CONTENT);
+      assert_eq!(base.get_doc("1.md").unwrap().text(), CONTENT);
+      // doesn't ask again about a skipped issue
+      let Unfixable = fix(issue, &mut base, &mut prompt, &mut decisions) else {
+        panic!()
+      };
+      assert_eq!(prompt.previews.len(), 1);
+    }
+
+    #[test]
+    fn accept_all() {
+      let (dir, mut base) = setup();
+      let mut prompt = Scripted {
+        answers: vec![Answer::AcceptAll],
+        previews: vec![],
+      };
+      let mut decisions = Decisions::default();
+      for issue in commands::check(&base, false).issues {
+        let Fixed(_) = fix(issue, &mut base, &mut prompt, &mut decisions) else {
+          panic!()
+        };
+      }
+      let want = "# One\n\n### text\n\n[Two](2.md)\n";
+      assert_eq!(test::load_file("1.md", dir.path()), want);
+      assert_eq!(prompt.previews.len(), 1);
+    }
+  }
+
+  mod preview {
+    use super::super::preview;
+
+    #[test]
+    fn changed_line() {
+      let before = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+      let after = "one\ntwo\nthree\nFOUR\nfive\nsix\nseven\n";
+      let want = "   2   two\n   3   three\n   4 - four\n     + FOUR\n   5   five\n   6   six\n";
+      assert_eq!(preview(before, after), want);
+    }
+
+    #[test]
+    fn added_lines_at_end() {
+      let before = "one\ntwo\n";
+      let after = "one\ntwo\n\nthree\n";
+      let want = "   1   one\n   2   two\n     + \n     + three\n";
+      assert_eq!(preview(before, after), want);
+    }
+
+    #[test]
+    fn unchanged() {
+      assert_eq!(preview("one\n", "one\n"), "  (the text doesn't change)\n");
+    }
+  }
+}
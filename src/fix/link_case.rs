@@ -0,0 +1,106 @@
+use super::Fix::CorrectedLinkCase;
+use crate::check::Location;
+use crate::fix::Result::{Failed, Fixed, Unfixable};
+use crate::{Tikibase, fix};
+
+/// replaces the given link target at the given location with the given correctly capitalized target
+pub fn correct_case(
+  base: &mut Tikibase,
+  location: Location,
+  target: String,
+  correct_target: String,
+) -> fix::Result {
+  let Some(doc) = base.get_doc_mut(&location.file) else {
+    return Unfixable;
+  };
+  let Some(line) = doc.line_mut(location.line) else {
+    return Unfixable;
+  };
+  let start = location.start as usize;
+  let end = (location.end as usize).min(line.text.len());
+  let Some(span) = line.text.get(start..end) else {
+    return Unfixable;
+  };
+  // the target of Markdown links follows "(", the target of HTML tags is quoted
+  let delimiters = [("(", ""), ("(<", ">"), ("\"", "\"")];
+  let Some(target_start) = delimiters.iter().find_map(|(before, after)| {
+    span
+      .find(&format!("{before}{target}{after}"))
+      .map(|pos| start + pos + before.len())
+  }) else {
+    return Unfixable;
+  };
+  line
+    .text
+    .replace_range(target_start..target_start + target.len(), &correct_target);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
+  Fixed(CorrectedLinkCase {
+    location,
+    target,
+    correct_target,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod correct_case {
+    use crate::check::Location;
+    use crate::fix::Fix::CorrectedLinkCase;
+    use crate::fix::Result::Fixed;
+    use crate::{Tikibase, test};
+    use big_s::S;
+    use indoc::indoc;
+
+    #[test]
+    fn markdown_and_html_links() {
+      let dir = camino_tempfile::tempdir().unwrap();
+      let content = indoc! {r#"
+        # One
+
+        see [one.md](one.md#section) and <a href="image.png">image.png</a>
+        "#};
+      test::create_file("1.md", content, dir.path());
+      let mut base = Tikibase::load(dir.path()).unwrap();
+      let location = Location {
+        file: S("1.md"),
+        line: 2,
+        start: 4,
+        end: 28,
+      };
+      let Fixed(have) = super::super::correct_case(
+        &mut base,
+        location.clone(),
+        S("one.md#section"),
+        S("One.md#section"),
+      ) else {
+        panic!("not fixed");
+      };
+      let want = CorrectedLinkCase {
+        location,
+        target: S("one.md#section"),
+        correct_target: S("One.md#section"),
+      };
+      pretty::assert_eq!(have, want);
+      let location = Location {
+        file: S("1.md"),
+        line: 2,
+        start: 33,
+        end: 66,
+      };
+      let Fixed(_) =
+        super::super::correct_case(&mut base, location, S("image.png"), S("Image.png"))
+      else {
+        panic!("not fixed");
+      };
+      let want = indoc! {r#"
+        # One
+
+        see [one.md](One.md#section) and <a href="Image.png">image.png</a>
+        "#};
+      pretty::assert_eq!(test::load_file("1.md", dir.path()), want);
+    }
+  }
+}
@@ -2,8 +2,8 @@ use super::Fix::AddedOccurrencesSection;
 use crate::check::Issue::{self, TitleRegexNoCaptures, TitleRegexTooManyCaptures};
 use crate::check::Location;
 use crate::database::{Line, Tikibase, section};
-use crate::fix;
 use crate::fix::Result::{Failed, Fixed};
+use crate::{fix, fspath};
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 use section::Section;
@@ -15,7 +15,6 @@ pub fn add_occurrences(
   path: String,
   title: &str,
 ) -> fix::Result {
-  let base_dir = base.root.clone();
   let title_regex = match base.dir.config.title_regex() {
     Ok(regex) => regex,
     Err(issue) => return Failed(issue),
@@ -26,30 +25,35 @@ pub fn add_occurrences(
   let occurrences_section = if let Some(section) = doc.section_with_human_title_mut("occurrences") {
     section
   } else {
-    // append a newline to the section before
-    doc.last_section_mut().push_line("");
+    // separate it from the section before by exactly one empty line
+    let last_section = doc.last_section_mut();
+    while last_section
+      .body
+      .last()
+      .is_some_and(|line| line.text.trim().is_empty())
+    {
+      last_section.body.pop();
+    }
+    last_section.push_line("");
 
     // insert occurrences section
     let occurrences_section = Section::new(doc.lines_count() + 1, "### occurrences", vec![""]);
     doc.content_sections.push(occurrences_section);
     doc.section_with_human_title_mut("occurrences").unwrap()
   };
-  let stripped_title = &strip_links(title);
-  let title = match &title_regex {
-    None => stripped_title,
-    Some(regex) => match extract_shortcut(stripped_title, regex) {
-      ExtractShortcutResult::ShortcutFound(shortcut) => shortcut,
-      ExtractShortcutResult::NoShortcutFound => stripped_title,
-      ExtractShortcutResult::Failed(issue) => return Failed(issue),
-    },
+  let title = match link_title(title, title_regex.as_ref()) {
+    Ok(title) => title,
+    Err(issue) => return Failed(issue),
   };
   occurrences_section.body.push(Line {
-    text: format!("- [{}]({})", title, &path),
+    text: format!("- [{}]({})", title, fspath::encode(&path)),
   });
 
   let line = occurrences_section.line_number;
   let end = occurrences_section.title_line.text.len() as u32;
-  doc.save(&base_dir);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(AddedOccurrencesSection {
     location: Location {
       file: location.file,
@@ -61,6 +65,19 @@ pub fn add_occurrences(
   })
 }
 
+/// provides the text to use for links to a document with the given title
+pub fn link_title(title: &str, title_regex: Option<&Regex>) -> Result<String, Issue> {
+  let stripped_title = strip_links(title);
+  match title_regex {
+    None => Ok(stripped_title.into_owned()),
+    Some(regex) => match extract_shortcut(&stripped_title, regex) {
+      ExtractShortcutResult::ShortcutFound(shortcut) => Ok(shortcut.to_owned()),
+      ExtractShortcutResult::NoShortcutFound => Ok(stripped_title.into_owned()),
+      ExtractShortcutResult::Failed(issue) => Err(issue),
+    },
+  }
+}
+
 /// tries to extract a shortcut defined by the given regex from the given title
 fn extract_shortcut<'a>(title: &'a str, regex: &Regex) -> ExtractShortcutResult<'a> {
   match regex.captures_len() {
@@ -3,7 +3,7 @@ use super::inconsistent_levels::title_at_level;
 use crate::check::Location;
 use crate::database::Tikibase;
 use crate::fix;
-use crate::fix::Result::Fixed;
+use crate::fix::Result::{Failed, Fixed};
 
 pub fn normalize_capitalization(
   base: &mut Tikibase,
@@ -12,13 +12,14 @@ pub fn normalize_capitalization(
   old_capitalization: String,
   new_capitalization: String,
 ) -> fix::Result {
-  let base_dir = base.root.clone();
   let doc = base.get_doc_mut(&location.file).unwrap();
   let section = doc
     .section_with_human_title_mut(&old_capitalization)
     .unwrap();
-  section.title_line.text = title_at_level(&new_capitalization, section_level as usize);
-  doc.save(&base_dir);
+  section.set_title(title_at_level(&new_capitalization, section_level as usize));
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(NormalizedSectionCapitalization {
     location,
     old_capitalization,
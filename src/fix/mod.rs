@@ -1,19 +1,53 @@
 //! Auto-fixing functionality
 
+mod backlinks_section;
+mod duplicate_sections;
 mod empty_section;
+mod encoding;
+mod footnotes;
 mod inconsistent_levels;
+pub mod interactive;
+mod link_case;
 mod missing_links;
 mod mix_cap_section;
 mod obsolete_occurrences_section;
+mod selection;
 mod unordered_sections;
 
 use crate::Tikibase;
 use crate::check::{Issue, Location};
+use crate::input::Rule;
+pub use selection::is_selected;
+
+/// provides the rule that fixes the given Issue, if it is auto-fixable
+pub fn rule(issue: &Issue) -> Option<Rule> {
+  match issue {
+    Issue::DuplicateSection { .. } => Some(Rule::DuplicateSection),
+    Issue::EmptySection { .. } => Some(Rule::EmptySection),
+    Issue::HeadingLevelDifferentThanConfigured { .. } | Issue::InconsistentHeadingLevel { .. } => {
+      Some(Rule::SectionLevel)
+    }
+    Issue::LinkWithWrongCase { .. } => Some(Rule::LinkCase),
+    Issue::MisnumberedFootnotes { .. } => Some(Rule::FootnoteNumbering),
+    Issue::MisplacedFootnoteDefinitions { .. } => Some(Rule::FootnotePlacement),
+    Issue::MissingLink { .. } => Some(Rule::MissingLink),
+    Issue::MixCapSection { .. } => Some(Rule::SectionCapitalization),
+    Issue::NonUtf8Document { .. } => Some(Rule::Encoding),
+    Issue::ObsoleteOccurrencesSection { .. } => Some(Rule::ObsoleteOccurrences),
+    Issue::StaleBacklink { .. } => Some(Rule::StaleBacklink),
+    Issue::UnorderedSections { .. } => Some(Rule::SectionOrder),
+    Issue::UnusedFootnote { .. } => Some(Rule::UnusedFootnote),
+    _ => None,
+  }
+}
 
 /// fixes the given Issue
 pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
   match issue {
     // actual fixes
+    Issue::DuplicateSection { location, title } => {
+      duplicate_sections::merge_sections(base, location, title)
+    }
     Issue::EmptySection { location, title } => empty_section::remove_section(base, title, location),
     Issue::HeadingLevelDifferentThanConfigured {
       location,
@@ -48,11 +82,22 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
         Result::Unfixable
       }
     }
+    Issue::LinkWithWrongCase {
+      location,
+      target,
+      correct_target,
+    } => link_case::correct_case(base, location, target, correct_target),
+    Issue::MisnumberedFootnotes { location } => footnotes::renumber(base, location),
+    Issue::MisplacedFootnoteDefinitions { location } => footnotes::move_definitions(base, location),
     Issue::MissingLink {
       location,
       path,
       title,
-    } => missing_links::add_occurrences(base, location, path, &title),
+    } => match base.dir.config.backlinks_section.clone() {
+      Some(section_title) => backlinks_section::regenerate(base, location, path, &section_title),
+      None => missing_links::add_occurrences(base, location, path, &title),
+    },
+    Issue::NonUtf8Document { location } => encoding::convert_to_utf8(base, location),
     Issue::MixCapSection {
       location,
       all_variants: _,
@@ -75,17 +120,32 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
     Issue::ObsoleteOccurrencesSection { location } => {
       obsolete_occurrences_section::remove_occurrences_section(base, location)
     }
+    Issue::StaleBacklink { location, path } => {
+      backlinks_section::remove_entry(base, location, path)
+    }
     Issue::UnorderedSections { location } => unordered_sections::sort_sections(base, location),
+    Issue::UnusedFootnote {
+      location,
+      identifier,
+    } => footnotes::remove_unused(base, location, identifier),
     // no-ops
     Issue::BrokenImage {
       location: _,
       target: _,
     }
     | Issue::CannotReadDirectory { path: _, err: _ }
+    | Issue::CannotReadFile {
+      file: _,
+      message: _,
+    }
     | Issue::CannotWriteConfigFile {
       file: _,
       message: _,
     }
+    | Issue::CannotWriteFile {
+      file: _,
+      message: _,
+    }
     | Issue::CannotWriteJsonSchemaFile {
       file: _,
       message: _,
@@ -94,12 +154,18 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
       message: _,
       location: _,
     }
-    | Issue::DocumentWithoutLinks { location: _ }
-    | Issue::DuplicateSection {
+    | Issue::BrokenExternalLink {
       location: _,
-      title: _,
+      url: _,
+      problem: _,
+    }
+    | Issue::DanglingSymlink {
+      location: _,
+      target: _,
     }
+    | Issue::DocumentWithoutLinks { location: _ }
     | Issue::EmptyDocument { path: _ }
+    | Issue::ExportDirInsideTikibase { path: _ }
     | Issue::InvalidConfigurationFile {
       message: _,
       location: _,
@@ -135,11 +201,27 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
       location: _,
       identifier: _,
     }
+    | Issue::MissingLinkDefinition {
+      location: _,
+      label: _,
+    }
     | Issue::LinkToSameDocument { location: _ }
     | Issue::LinkWithoutTarget { location: _ }
+    | Issue::FileChangedSinceFix { file: _ }
     | Issue::NoTitleSection { location: _ }
+    | Issue::NothingToUndo
     | Issue::OrphanedResource { location: _ }
+    | Issue::OscillatingFix { location: _ }
+    | Issue::RedirectedExternalLink {
+      location: _,
+      url: _,
+      target: _,
+    }
     | Issue::SectionWithoutHeader { location: _ }
+    | Issue::SymlinkCycle {
+      location: _,
+      target: _,
+    }
     | Issue::TitleRegexNoCaptures { regex: _ }
     | Issue::TitleRegexTooManyCaptures {
       regex: _,
@@ -152,9 +234,10 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
       title: _,
       allowed_titles: _,
     }
-    | Issue::UnusedFootnote {
+    | Issue::UnsettledFixes { file: _, passes: _ }
+    | Issue::UnusedLinkDefinition {
       location: _,
-      identifier: _,
+      label: _,
     } => Result::Unfixable,
   }
 }
@@ -162,10 +245,31 @@ pub fn fix(issue: Issue, base: &mut Tikibase) -> Result {
 /// documents the fixes that this linter performs
 #[derive(Debug, Eq, PartialEq)]
 pub enum Fix {
+  AddedBacklink {
+    location: Location,
+    target: String,
+    section_title: String,
+  },
   AddedOccurrencesSection {
     location: Location,
     target: String,
   },
+  ConvertedToUtf8 {
+    location: Location,
+  },
+  CorrectedLinkCase {
+    location: Location,
+    target: String,
+    correct_target: String,
+  },
+  MergedDuplicateSections {
+    location: Location,
+    title: String,
+    count: usize,
+  },
+  MovedFootnoteDefinitions {
+    location: Location,
+  },
   NormalizedSectionCapitalization {
     location: Location,
     old_capitalization: String,
@@ -184,11 +288,48 @@ pub enum Fix {
   RemovedObsoleteOccurrencesSection {
     location: Location,
   },
+  RemovedStaleBacklink {
+    location: Location,
+    path: String,
+  },
+  RemovedUnusedFootnote {
+    location: Location,
+    identifier: String,
+  },
+  RenumberedFootnotes {
+    location: Location,
+  },
+  RestoredFile {
+    file: String,
+  },
   SortedSections {
     location: Location,
   },
 }
 
+impl Fix {
+  /// provides the path of the file that this fix changed
+  pub fn file(&self) -> &str {
+    match self {
+      Self::AddedBacklink { location, .. }
+      | Self::AddedOccurrencesSection { location, .. }
+      | Self::ConvertedToUtf8 { location }
+      | Self::CorrectedLinkCase { location, .. }
+      | Self::MergedDuplicateSections { location, .. }
+      | Self::MovedFootnoteDefinitions { location }
+      | Self::NormalizedSectionCapitalization { location, .. }
+      | Self::NormalizedSectionLevel { location, .. }
+      | Self::RemovedEmptySection { location, .. }
+      | Self::RemovedObsoleteOccurrencesSection { location }
+      | Self::RemovedStaleBacklink { location, .. }
+      | Self::RemovedUnusedFootnote { location, .. }
+      | Self::RenumberedFootnotes { location }
+      | Self::SortedSections { location } => &location.file,
+      Self::RestoredFile { file } => file,
+    }
+  }
+}
+
 /// result of a fix operation
 pub enum Result {
   /// the issue was fixed
@@ -0,0 +1,105 @@
+use super::rule;
+use crate::check::Issue;
+use crate::input::Selection;
+
+/// indicates whether the given selection includes fixing the given issue
+pub fn is_selected(issue: &Issue, selection: &Selection) -> bool {
+  let Some(rule) = rule(issue) else {
+    return true;
+  };
+  if !selection.only.is_empty() && !selection.only.contains(&rule) {
+    return false;
+  }
+  if selection.skip.contains(&rule) {
+    return false;
+  }
+  if selection.paths.is_empty() {
+    return true;
+  }
+  issue.location().is_some_and(|location| {
+    selection
+      .paths
+      .iter()
+      .any(|path| is_inside(&location.file, path))
+  })
+}
+
+/// indicates whether the given file is the given path or inside the folder with the given path
+fn is_inside(file: &str, path: &str) -> bool {
+  let path = path.trim_start_matches("./").trim_end_matches('/');
+  if path.is_empty() || path == "." {
+    return true;
+  }
+  file == path
+    || file
+      .strip_prefix(path)
+      .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod is_selected {
+    use super::super::is_selected;
+    use crate::check::{Issue, Location};
+    use crate::input::{Rule, Selection};
+    use big_s::S;
+
+    fn empty_section(file: &str) -> Issue {
+      Issue::EmptySection {
+        location: Location {
+          file: file.into(),
+          line: 2,
+          start: 0,
+          end: 5,
+        },
+        title: S("foo"),
+      }
+    }
+
+    #[test]
+    fn everything() {
+      let selection = Selection::default();
+      assert!(is_selected(&empty_section("1.md"), &selection));
+    }
+
+    #[test]
+    fn only_matching() {
+      let selection = Selection {
+        only: vec![Rule::SectionOrder, Rule::EmptySection],
+        ..Selection::default()
+      };
+      assert!(is_selected(&empty_section("1.md"), &selection));
+    }
+
+    #[test]
+    fn only_other() {
+      let selection = Selection {
+        only: vec![Rule::SectionOrder],
+        ..Selection::default()
+      };
+      assert!(!is_selected(&empty_section("1.md"), &selection));
+    }
+
+    #[test]
+    fn skipped() {
+      let selection = Selection {
+        skip: vec![Rule::EmptySection],
+        ..Selection::default()
+      };
+      assert!(!is_selected(&empty_section("1.md"), &selection));
+    }
+
+    #[test]
+    fn paths() {
+      let selection = Selection {
+        paths: vec![S("notes/"), S("2.md")],
+        ..Selection::default()
+      };
+      assert!(is_selected(&empty_section("notes/1.md"), &selection));
+      assert!(is_selected(&empty_section("2.md"), &selection));
+      assert!(!is_selected(&empty_section("1.md"), &selection));
+      assert!(!is_selected(&empty_section("notes-old/1.md"), &selection));
+    }
+  }
+}
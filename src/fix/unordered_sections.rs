@@ -1,15 +1,16 @@
-use super::Result::Fixed;
+use super::Result::{Failed, Fixed};
 use crate::check::Location;
 use crate::database::Section;
 use crate::fix::Fix::SortedSections;
 use crate::{Tikibase, fix};
 
 pub fn sort_sections(base: &mut Tikibase, location: Location) -> fix::Result {
-  let base_dir = base.root.clone();
   let sections = base.dir.config.sections.clone().unwrap();
   let doc = base.get_doc_mut(&location.file).unwrap();
   doc.content_sections = reorder(&mut doc.content_sections, &sections);
-  doc.save(&base_dir);
+  if let Err(issue) = base.save_doc(&location.file) {
+    return Failed(issue);
+  }
   Fixed(SortedSections { location })
 }
 
@@ -0,0 +1,43 @@
+use percent_encoding::percent_decode_str;
+
+/// provides the filesystem path that the given link target refers to,
+/// i.e. the link target with percent-encoded characters like `%20` decoded
+pub fn decode(target: &str) -> String {
+  percent_decode_str(target).decode_utf8_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod decode {
+    use big_s::S;
+
+    #[test]
+    fn encoded_space() {
+      let have = super::super::decode("my%20note.md");
+      let want = S("my note.md");
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn encoded_unicode() {
+      let have = super::super::decode("caf%C3%A9.md");
+      let want = S("café.md");
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn plain() {
+      let have = super::super::decode("sub/one.md");
+      let want = S("sub/one.md");
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn invalid_encoding() {
+      let have = super::super::decode("100%.md");
+      let want = S("100%.md");
+      assert_eq!(have, want);
+    }
+  }
+}
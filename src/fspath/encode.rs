@@ -0,0 +1,49 @@
+/// characters that can't appear unencoded in the target of a Markdown link
+const RESERVED: &[char] = &[' ', '"', '#', '%', '(', ')', '<', '>'];
+
+/// provides the link target for the given filesystem path,
+/// i.e. the path with characters that Markdown links can't contain percent-encoded
+pub fn encode(path: &str) -> String {
+  let mut result = String::with_capacity(path.len());
+  for c in path.chars() {
+    if RESERVED.contains(&c) || c.is_control() {
+      let mut buffer = [0; 4];
+      for byte in c.encode_utf8(&mut buffer).bytes() {
+        result.push_str(&format!("%{byte:02X}"));
+      }
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod encode {
+    use super::super::encode;
+    use crate::fspath::decode;
+
+    #[test]
+    fn space() {
+      assert_eq!(encode("two words.md"), "two%20words.md");
+    }
+
+    #[test]
+    fn parentheses_and_percent() {
+      assert_eq!(encode("a (100%).md"), "a%20%28100%25%29.md");
+    }
+
+    #[test]
+    fn unchanged() {
+      assert_eq!(encode("../sub/äpfel.md"), "../sub/äpfel.md");
+    }
+
+    #[test]
+    fn reverses_decode() {
+      let path = "sub dir/#1 <draft>.md";
+      assert_eq!(decode(&encode(path)), path);
+    }
+  }
+}
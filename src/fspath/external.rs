@@ -0,0 +1,22 @@
+/// indicates whether the given link target points to a resource outside of the Tikibase,
+/// e.g. a website
+pub fn is_external(target: &str) -> bool {
+  target.contains("://")
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod is_external {
+
+    #[test]
+    fn url() {
+      assert!(super::super::is_external("https://google.com"));
+    }
+
+    #[test]
+    fn local_file() {
+      assert!(!super::super::is_external("http-guide.md"));
+    }
+  }
+}
@@ -1,9 +1,17 @@
 //! Functions to handle filesystem paths
 
+mod decode;
+mod encode;
+mod external;
 mod join;
 mod normalize;
 mod relative;
+mod resolve;
 
+pub use decode::decode;
+pub use encode::encode;
+pub use external::is_external;
 pub use join::join;
 pub use normalize::normalize;
-pub use relative::relative;
+pub use relative::{dirname, relative};
+pub use resolve::{resolve, resolve_target};
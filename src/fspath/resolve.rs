@@ -0,0 +1,122 @@
+use super::{decode, is_external, join, normalize};
+
+/// provides the path relative to the Tikibase root of the file that the given link target,
+/// located in a document in the given directory, points to.
+/// Root-relative link targets start at the Tikibase root, or at the given base path if the Tikibase is published under one.
+/// Provides nothing if a root-relative link target points outside of that base path.
+pub fn resolve(dir: &str, target_file: &str, base_path: Option<&str>) -> Option<String> {
+  let target_file = decode(target_file);
+  let Some(absolute) = target_file.strip_prefix('/') else {
+    return Some(normalize(&join(dir, &target_file)));
+  };
+  let base_path = base_path.unwrap_or_default().trim_matches('/');
+  if base_path.is_empty() {
+    return Some(normalize(absolute));
+  }
+  let rest = absolute.strip_prefix(base_path)?;
+  if rest.is_empty() {
+    return Some(String::new());
+  }
+  rest.strip_prefix('/').map(normalize)
+}
+
+/// provides the path relative to the Tikibase root of the file that the given link target,
+/// located in a document in the given directory, points to, ignoring its anchor.
+/// Provides nothing if the link target doesn't point to a local file.
+pub fn resolve_target(dir: &str, target: &str, base_path: Option<&str>) -> Option<String> {
+  if is_external(target) {
+    return None;
+  }
+  let target_file = match target.split_once('#') {
+    Some((base, _anchor)) => base,
+    None => target,
+  };
+  if target_file.is_empty() {
+    return None;
+  }
+  resolve(dir, target_file, base_path)
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod resolve {
+    use big_s::S;
+
+    #[test]
+    fn relative() {
+      let have = super::super::resolve("sub", "../one.md", None);
+      assert_eq!(have, Some(S("one.md")));
+    }
+
+    #[test]
+    fn root_relative() {
+      let have = super::super::resolve("sub", "/topics/rust.md", None);
+      assert_eq!(have, Some(S("topics/rust.md")));
+    }
+
+    #[test]
+    fn root_relative_encoded() {
+      let have = super::super::resolve("sub", "/my%20note.md", None);
+      assert_eq!(have, Some(S("my note.md")));
+    }
+
+    #[test]
+    fn inside_base_path() {
+      let have = super::super::resolve("sub", "/wiki/topics/rust.md", Some("/wiki/"));
+      assert_eq!(have, Some(S("topics/rust.md")));
+    }
+
+    #[test]
+    fn outside_base_path() {
+      let have = super::super::resolve("sub", "/wikipedia/rust.md", Some("/wiki"));
+      assert_eq!(have, None);
+    }
+
+    #[test]
+    fn relative_with_base_path() {
+      let have = super::super::resolve("sub", "one.md", Some("/wiki"));
+      assert_eq!(have, Some(S("sub/one.md")));
+    }
+  }
+
+  mod resolve_target {
+    use big_s::S;
+
+    #[test]
+    fn same_dir() {
+      let have = super::super::resolve_target("", "one.md", None);
+      assert_eq!(have, Some(S("one.md")));
+    }
+
+    #[test]
+    fn parent_dir_with_anchor() {
+      let have = super::super::resolve_target("sub", "../one.md#foo", None);
+      assert_eq!(have, Some(S("one.md")));
+    }
+
+    #[test]
+    fn percent_encoded() {
+      let have = super::super::resolve_target("", "my%20note.md#foo", None);
+      assert_eq!(have, Some(S("my note.md")));
+    }
+
+    #[test]
+    fn root_relative_with_base_path() {
+      let have = super::super::resolve_target("sub", "/wiki/one.md#foo", Some("/wiki"));
+      assert_eq!(have, Some(S("one.md")));
+    }
+
+    #[test]
+    fn anchor_only() {
+      let have = super::super::resolve_target("sub", "#foo", None);
+      assert_eq!(have, None);
+    }
+
+    #[test]
+    fn external() {
+      let have = super::super::resolve_target("", "https://google.com", None);
+      assert_eq!(have, None);
+    }
+  }
+}
@@ -28,18 +28,109 @@ impl Default for Format {
 }
 
 /// the subcommands of the CLI app
-#[derive(clap::Subcommand, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(clap::Subcommand, Clone, Debug, Eq, PartialEq)]
 pub enum Command {
+  /// Lists all documents that link to the given file
+  Backlinks {
+    /// path of the file, relative to the Tikibase root
+    target: String,
+  },
   /// Prints all issues
-  Check,
+  Check {
+    /// also check links to external websites
+    #[clap(long)]
+    external: bool,
+  },
+  /// Exports this Tikibase into another format
+  Export {
+    #[clap(subcommand)]
+    format: ExportFormat,
+  },
   /// Corrects all auto-fixable issues
-  Fix,
+  Fix {
+    /// store the original content of changed files in .tikibase/backup
+    #[clap(long)]
+    backup: bool,
+    /// ask before applying each fix
+    #[clap(long, short)]
+    interactive: bool,
+    #[clap(flatten)]
+    selection: Selection,
+  },
   /// Scaffolds a configuration file
   Init,
   /// Export the JSON Schema for the configuration file
   JsonSchema,
   /// Corrects all auto-fixable issues and prints the remaining ("pitstop")
-  P,
+  P {
+    /// store the original content of changed files in .tikibase/backup
+    #[clap(long)]
+    backup: bool,
+    #[clap(flatten)]
+    selection: Selection,
+  },
+  /// Searches the text of all documents, most relevant first.
+  /// Supports "exact phrases", prefix* matches, and section:title scopes.
+  Search {
+    /// the search query
+    query: String,
+  },
   /// Displays statistics about this Tikibase
   Stats,
+  /// Restores the files changed by the last fix
+  Undo,
+}
+
+/// the issues that `fix` and `p` fix
+#[derive(clap::Args, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Selection {
+  /// fix only issues of this kind (can be given multiple times)
+  #[clap(arg_enum, long, value_name = "RULE")]
+  pub only: Vec<Rule>,
+  /// don't fix issues of this kind (can be given multiple times)
+  #[clap(arg_enum, long, value_name = "RULE")]
+  pub skip: Vec<Rule>,
+  /// fix only issues in these files or folders
+  pub paths: Vec<String>,
+}
+
+/// the kinds of issues that Tikibase can fix
+#[derive(clap::ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rule {
+  /// merge sections with the same title
+  DuplicateSection,
+  /// remove empty sections
+  EmptySection,
+  /// convert Windows-1252 files to UTF-8
+  Encoding,
+  /// number numeric footnotes in the order of their first reference
+  FootnoteNumbering,
+  /// move footnote definitions to the end of the document
+  FootnotePlacement,
+  /// correct the capitalization of links
+  LinkCase,
+  /// add missing backlinks
+  MissingLink,
+  /// remove obsolete "occurrences" sections
+  ObsoleteOccurrences,
+  /// normalize the capitalization of section titles
+  SectionCapitalization,
+  /// normalize the heading levels of sections
+  SectionLevel,
+  /// sort sections in the configured order
+  SectionOrder,
+  /// remove stale entries from the backlinks section
+  StaleBacklink,
+  /// remove unused footnote definitions
+  UnusedFootnote,
+}
+
+/// the formats that a Tikibase can be exported to
+#[derive(clap::Subcommand, Clone, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+  /// Static HTML website
+  Html {
+    /// the directory to write the website into
+    outdir: String,
+  },
 }
@@ -2,6 +2,7 @@ mod check;
 pub mod commands;
 pub mod config;
 mod database;
+mod export;
 mod fix;
 mod fspath;
 pub mod input;
@@ -13,6 +14,7 @@ use camino::Utf8Path;
 pub use config::Config;
 use database::Tikibase;
 pub use fix::Fix;
+use fix::interactive::Terminal;
 use input::Command;
 pub use output::{Message, Messages};
 pub use prelude::{Result, UserError};
@@ -25,16 +27,44 @@ pub use prelude::{Result, UserError};
 /// runs the given Command in the given directory, returns structured data
 #[must_use]
 pub fn run<P: AsRef<Utf8Path>>(command: input::Command, dir: P) -> Messages {
+  if command == Command::Undo {
+    return Messages::from_outcome(commands::undo(dir.as_ref()));
+  }
   let mut base = match Tikibase::load(dir.as_ref()) {
     Ok(base) => base,
     Err(issues) => return Messages::from_issues(issues),
   };
   let outcome = match command {
-    Command::Check => commands::check(&base),
+    Command::Backlinks { target } => {
+      return Messages::from_backlinks(commands::backlinks(&base, &target));
+    }
+    Command::Search { query } => {
+      return Messages::from_hits(commands::search(&base, &query));
+    }
+    Command::Check { external } => commands::check(&base, external),
+    Command::Export { format } => commands::export(&base, format),
     Command::Stats => commands::stats(&base),
-    Command::Fix => commands::fix(&mut base),
-    Command::P => commands::pitstop(&mut base),
-    Command::Init | Command::JsonSchema => panic!(), // handled above
+    Command::Fix {
+      backup,
+      interactive,
+      selection,
+    } => {
+      if backup {
+        base.enable_backups();
+      }
+      if interactive {
+        commands::fix_interactively(&mut base, &selection, &mut Terminal)
+      } else {
+        commands::fix(&mut base, &selection)
+      }
+    }
+    Command::P { backup, selection } => {
+      if backup {
+        base.enable_backups();
+      }
+      commands::pitstop(&mut base, &selection)
+    }
+    Command::Init | Command::JsonSchema | Command::Undo => panic!(), // handled above
   };
   Messages::from_outcome(outcome)
 }
@@ -25,19 +25,20 @@ fn inner() -> tikibase::Result<()> {
   if args.command == Command::JsonSchema {
     return tikibase::commands::json_schema();
   }
-  let messages = run(args.command, ".");
+  let messages = run(args.command.clone(), ".");
   match args.format {
-    Text => print_text(&messages, args.command),
+    Text => print_text(&messages),
     Json => print_json(&messages.all()),
   };
   Ok(())
 }
 
-fn print_text(messages: &Messages, command: Command) {
-  if command != Command::Fix {
-    for issue in &messages.issues {
-      println!("{}", issue.to_text());
-    }
+fn print_text(messages: &Messages) {
+  for result in &messages.results {
+    println!("{}", result.to_text());
+  }
+  for issue in &messages.issues {
+    println!("{}", issue.to_text());
   }
   if messages.has_issues_and_fixes() {
     println!();
@@ -3,6 +3,7 @@
 use crate::Fix;
 use crate::check::Issue;
 use crate::commands::Outcome;
+use crate::database::{Backlink, Hit, JOURNAL_DIR};
 use big_s::S;
 use serde::Serialize;
 
@@ -28,6 +29,32 @@ impl Message {
     }
   }
 
+  /// provides a Message instance describing the given Backlink
+  #[must_use]
+  pub fn from_backlink(backlink: Backlink) -> Self {
+    Self {
+      text: format!("references \"{}\"", backlink.target),
+      file: backlink.source,
+      line: Some(backlink.line),
+      start: Some(backlink.start),
+      end: Some(backlink.end),
+      fixable: false,
+    }
+  }
+
+  /// provides a Message instance describing the given search hit
+  #[must_use]
+  pub fn from_hit(hit: Hit) -> Self {
+    Self {
+      text: hit.text,
+      file: hit.file,
+      line: Some(hit.line),
+      start: Some(hit.start),
+      end: Some(hit.end),
+      fixable: false,
+    }
+  }
+
   /// provides a Message instance summarizing the given Fix
   #[must_use]
   pub fn from_fix(fix: Fix) -> Self {
@@ -40,6 +67,18 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Fix::AddedBacklink {
+        location,
+        target,
+        section_title,
+      } => Self {
+        text: format!("added {target} to {section_title} section"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Fix::AddedOccurrencesSection { location, target } => Self {
         text: format!("added {target} to occurrences section"),
         file: location.file,
@@ -48,6 +87,46 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Fix::ConvertedToUtf8 { location } => Self {
+        text: S("converted file from Windows-1252 to UTF-8"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::CorrectedLinkCase {
+        location,
+        target: _,
+        correct_target,
+      } => Self {
+        text: format!(r#"corrected capitalization of link to "{correct_target}""#),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::MergedDuplicateSections {
+        location,
+        title,
+        count,
+      } => Self {
+        text: format!("merged {count} \"{title}\" sections"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::MovedFootnoteDefinitions { location } => Self {
+        text: S("moved the footnote definitions to the end of the document"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Fix::NormalizedSectionCapitalization {
         location,
         old_capitalization,
@@ -85,6 +164,41 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Fix::RemovedStaleBacklink { location, path } => Self {
+        text: format!("removed stale backlink to {path}"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::RemovedUnusedFootnote {
+        location,
+        identifier,
+      } => Self {
+        text: format!("removed unused footnote [^{identifier}]"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::RenumberedFootnotes { location } => Self {
+        text: S("renumbered the footnotes in the order of their first reference"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Fix::RestoredFile { file } => Self {
+        text: S("restored the content from before the last fix"),
+        file,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Fix::SortedSections { location } => Self {
         text: S("fixed section order"),
         file: location.file,
@@ -100,6 +214,18 @@ impl Message {
   #[must_use]
   pub fn from_issue(issue: Issue) -> Self {
     match issue {
+      Issue::BrokenExternalLink {
+        location,
+        url,
+        problem,
+      } => Self {
+        text: format!("broken external link to {url}: {problem}"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Issue::BrokenImage { location, target } => Self {
         text: format!("image link to non-existing file \"{target}\""),
         file: location.file,
@@ -127,6 +253,14 @@ impl Message {
         end: None,
         fixable: false,
       },
+      Issue::CannotReadFile { file, message } => Self {
+        text: format!("cannot read file: {message}"),
+        file,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Issue::CannotWriteConfigFile { file, message } => Self {
         text: format!("cannot create configuration file: {message}"),
         file,
@@ -135,6 +269,14 @@ impl Message {
         end: None,
         fixable: false,
       },
+      Issue::CannotWriteFile { file, message } => Self {
+        text: format!("cannot write file: {message}"),
+        file,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Issue::CannotWriteJsonSchemaFile { file, message } => Self {
         text: format!("cannot write JSON Schema file: {message}"),
         file,
@@ -143,6 +285,14 @@ impl Message {
         end: None,
         fixable: false,
       },
+      Issue::DanglingSymlink { location, target } => Self {
+        text: format!("symlink points to non-existing \"{target}\""),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Issue::DocumentWithoutLinks { location } => Self {
         text: S("document is not connected to any other documents"),
         file: location.file,
@@ -157,7 +307,7 @@ impl Message {
         line: Some(location.line),
         start: Some(location.start),
         end: Some(location.end),
-        fixable: false,
+        fixable: true,
       },
       Issue::EmptyDocument { path } => Self {
         text: S("no content"),
@@ -175,6 +325,24 @@ impl Message {
         end: Some(location.end),
         fixable: true,
       },
+      Issue::ExportDirInsideTikibase { path } => Self {
+        text: S(
+          "the export directory is inside the Tikibase, choose one outside of it or whose name starts with a dot",
+        ),
+        file: path,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
+      Issue::FileChangedSinceFix { file } => Self {
+        text: S("file changed since the last fix, not undoing the fix"),
+        file,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Issue::HeadingLevelDifferentThanConfigured {
         location,
         configured_level,
@@ -305,6 +473,20 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Issue::LinkWithWrongCase {
+        location,
+        target,
+        correct_target,
+      } => Self {
+        text: format!(
+          r#"link to "{target}" has the wrong capitalization, should be "{correct_target}""#
+        ),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: true,
+      },
       Issue::LinkWithoutTarget { location } => Self {
         text: S("link without target"),
         file: location.file,
@@ -325,6 +507,24 @@ impl Message {
         end: Some(location.end),
         fixable: true,
       },
+      Issue::MisnumberedFootnotes { location } => Self {
+        text: S("footnotes aren't numbered in the order of their first reference"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: true,
+      },
+      Issue::MisplacedFootnoteDefinitions { location } => Self {
+        text: S(
+          "footnote definitions aren't at the end of the document in the order of their first reference",
+        ),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: true,
+      },
       Issue::MissingFootnote {
         location,
         identifier: index,
@@ -336,6 +536,14 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Issue::MissingLinkDefinition { location, label } => Self {
+        text: format!("link definition [{label}] doesn't exist"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Issue::MixCapSection {
         location,
         all_variants,
@@ -376,6 +584,22 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Issue::NonUtf8Document { location } => Self {
+        text: S("file is not valid UTF-8, reading it as Windows-1252"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: true,
+      },
+      Issue::NothingToUndo => Self {
+        text: S("no fix to undo"),
+        file: JOURNAL_DIR.into(),
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Issue::ObsoleteOccurrencesSection { location } => Self {
         text: S("obsolete \"occurrences\" section"),
         file: location.file,
@@ -392,6 +616,26 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Issue::OscillatingFix { location } => Self {
+        text: S("fixes keep undoing each other here, stopped fixing this file"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
+      Issue::RedirectedExternalLink {
+        location,
+        url,
+        target,
+      } => Self {
+        text: format!("external link to {url} permanently redirects to {target}"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Issue::SectionWithoutHeader { location } => Self {
         text: S("section with empty title"),
         file: location.file,
@@ -400,6 +644,22 @@ impl Message {
         end: Some(location.end),
         fixable: false,
       },
+      Issue::StaleBacklink { location, path } => Self {
+        text: format!("stale backlink to {path}"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: true,
+      },
+      Issue::SymlinkCycle { location, target } => Self {
+        text: format!("symlink to \"{target}\" creates a cycle"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
+        fixable: false,
+      },
       Issue::TitleRegexNoCaptures { regex } => Self {
         text: format!(
           "The regular expression in the \"titleRegEx\" entry ({regex}) doesn't contain a capture group"
@@ -466,6 +726,14 @@ impl Message {
         end: Some(location.end),
         fixable: true,
       },
+      Issue::UnsettledFixes { file, passes } => Self {
+        text: format!("fixes did not settle after {passes} passes without progress"),
+        file,
+        line: None,
+        start: None,
+        end: None,
+        fixable: false,
+      },
       Issue::UnusedFootnote {
         location,
         identifier,
@@ -475,6 +743,14 @@ impl Message {
         line: Some(location.line),
         start: Some(location.start),
         end: Some(location.end),
+        fixable: true,
+      },
+      Issue::UnusedLinkDefinition { location, label } => Self {
+        text: format!("unused link definition [{label}]"),
+        file: location.file,
+        line: Some(location.line),
+        start: Some(location.start),
+        end: Some(location.end),
         fixable: false,
       },
     }
@@ -483,6 +759,8 @@ impl Message {
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct Messages {
+  /// messages for the results of queries
+  pub results: Vec<Message>,
   /// messages for identified issues
   pub issues: Vec<Message>,
   /// messages for fixed issues
@@ -495,6 +773,7 @@ impl Messages {
   #[must_use]
   pub fn all(mut self) -> Vec<Message> {
     let mut result = vec![];
+    result.append(&mut self.results);
     result.append(&mut self.issues);
     result.append(&mut self.fixes);
     result
@@ -503,12 +782,30 @@ impl Messages {
   /// indicates whether there are any messages
   #[must_use]
   pub fn is_empty(&self) -> bool {
-    self.issues.is_empty() && self.fixes.is_empty()
+    self.results.is_empty() && self.issues.is_empty() && self.fixes.is_empty()
+  }
+
+  /// provides `Messages` listing the given backlinks
+  #[must_use]
+  pub fn from_backlinks(backlinks: Vec<Backlink>) -> Self {
+    Self {
+      results: backlinks.into_iter().map(Message::from_backlink).collect(),
+      ..Self::default()
+    }
+  }
+
+  #[must_use]
+  pub fn from_hits(hits: Vec<Hit>) -> Self {
+    Self {
+      results: hits.into_iter().map(Message::from_hit).collect(),
+      ..Self::default()
+    }
   }
 
   #[must_use]
   pub fn from_issue(issue: Issue) -> Self {
     Self {
+      results: vec![],
       issues: vec![Message::from_issue(issue)],
       fixes: vec![],
       exit_code: 1,
@@ -519,6 +816,7 @@ impl Messages {
   pub fn from_issues(issues: Vec<Issue>) -> Self {
     let exit_code = issues.len() as u8;
     Self {
+      results: vec![],
       issues: issues.into_iter().map(Message::from_issue).collect(),
       fixes: vec![],
       exit_code,
@@ -0,0 +1,12 @@
+use camino::Utf8Path;
+use fs_err as fs;
+
+/// creates a symlink with the given name inside the given directory that points to the given target
+#[cfg(unix)]
+pub fn create_symlink<P1: AsRef<Utf8Path>>(name: P1, target: &str, dir: &Utf8Path) {
+  let name = name.as_ref();
+  if let Some(parent) = name.parent() {
+    fs::create_dir_all(dir.join(parent)).unwrap();
+  }
+  std::os::unix::fs::symlink(target, dir.join(name)).unwrap();
+}
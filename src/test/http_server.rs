@@ -0,0 +1,71 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::io::BufReader;
+use std::io::prelude::*;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+/// a minimal local HTTP server that stands in for external websites in tests
+pub struct HttpServer {
+  /// the URL under which this server is reachable, without trailing slash
+  pub url: String,
+  requests: Arc<AtomicUsize>,
+}
+
+impl HttpServer {
+  /// starts a server that answers requests to the given paths with the given status line and headers,
+  /// e.g. ("/moved", "301 Moved Permanently\r\nLocation: /new"), and all other requests with 404
+  #[must_use]
+  pub fn start(routes: &[(&str, &str)]) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let routes: Vec<(String, String)> = routes
+      .iter()
+      .map(|(path, response)| ((*path).to_owned(), (*response).to_owned()))
+      .collect();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        counter.fetch_add(1, Ordering::SeqCst);
+        respond(stream, &routes);
+      }
+    });
+    Self { url, requests }
+  }
+
+  /// provides the number of requests this server has received so far
+  #[must_use]
+  pub fn requests(&self) -> usize {
+    self.requests.load(Ordering::SeqCst)
+  }
+
+  /// provides a local URL that no server listens on
+  #[must_use]
+  pub fn unused_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}/", listener.local_addr().unwrap())
+  }
+}
+
+fn respond(stream: TcpStream, routes: &[(String, String)]) {
+  let mut reader = BufReader::new(&stream);
+  let mut request_line = String::new();
+  if reader.read_line(&mut request_line).is_err() {
+    return;
+  }
+  // skip the headers
+  let mut header = String::new();
+  while reader.read_line(&mut header).is_ok_and(|len| len > 2) {
+    header.clear();
+  }
+  let path = request_line.split(' ').nth(1).unwrap_or_default();
+  let response = routes
+    .iter()
+    .find(|(route, _response)| route == path)
+    .map_or("404 Not Found", |(_route, response)| response.as_str());
+  let _ = write!(
+    &stream,
+    "HTTP/1.1 {response}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+  );
+}
@@ -1,9 +1,14 @@
 //! functions used in both unit and end-to-end tests
 
 mod create_file;
+mod create_symlink;
+mod http_server;
 mod load_file;
 mod trim_end;
 
 pub use create_file::create_file;
+#[cfg(unix)]
+pub use create_symlink::create_symlink;
+pub use http_server::HttpServer;
 pub use load_file::load_file;
 use trim_end::trim_end;
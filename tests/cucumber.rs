@@ -1,8 +1,11 @@
 use ahash::AHashMap;
 use big_s::S;
+use clap::StructOpt;
 use cucumber::gherkin::Step;
 use cucumber::{World, given, then, when};
-use tikibase::input::Command;
+use std::io::Write;
+use std::process::Stdio;
+use tikibase::input::{Arguments, Command, ExportFormat, Selection};
 use tikibase::{self, Messages, test};
 
 #[derive(Debug, World)]
@@ -46,19 +49,94 @@ fn file(world: &mut MyWorld, filename: String) {
   test::create_file(&filename, "content", world.dir.path());
 }
 
+#[given(expr = "file {string} with Latin-1 content:")]
+fn file_with_latin1_content(world: &mut MyWorld, step: &Step, filename: String) {
+  let content = step.docstring.as_ref().unwrap().trim();
+  let bytes: Vec<u8> = content.chars().map(|c| u8::try_from(c).unwrap()).collect();
+  fs_err::write(world.dir.path().join(&filename), bytes).unwrap();
+}
+
+#[given(expr = "symlink {string} pointing to {string}")]
+fn symlink(world: &mut MyWorld, name: String, target: String) {
+  test::create_symlink(&name, &target, world.dir.path());
+}
+
+#[when(expr = "listing the backlinks of {string}")]
+fn listing_backlinks(world: &mut MyWorld, target: String) {
+  world.output = tikibase::run(Command::Backlinks { target }, world.dir.path());
+}
+
+#[when(expr = "searching for {string}")]
+fn searching(world: &mut MyWorld, query: String) {
+  world.output = tikibase::run(Command::Search { query }, world.dir.path());
+}
+
+#[when(expr = "exporting HTML into {string}")]
+fn exporting_html(world: &mut MyWorld, outdir: String) {
+  let format = ExportFormat::Html { outdir };
+  world.output = tikibase::run(Command::Export { format }, world.dir.path());
+}
+
 #[when("checking")]
 fn checking(world: &mut MyWorld) {
-  world.output = tikibase::run(Command::Check, world.dir.path());
+  world.output = tikibase::run(Command::Check { external: false }, world.dir.path());
+}
+
+#[when("checking external links")]
+fn checking_external_links(world: &mut MyWorld) {
+  world.output = tikibase::run(Command::Check { external: true }, world.dir.path());
 }
 
 #[when("doing a pitstop")]
 fn doing_a_pitstop(world: &mut MyWorld) {
-  world.output = tikibase::run(Command::P, world.dir.path());
+  world.output = tikibase::run(
+    Command::P {
+      backup: false,
+      selection: Selection::default(),
+    },
+    world.dir.path(),
+  );
 }
 
 #[when("fixing")]
 fn fixing(world: &mut MyWorld) {
-  world.output = tikibase::run(Command::Fix, world.dir.path());
+  world.output = tikibase::run(
+    Command::Fix {
+      backup: false,
+      interactive: false,
+      selection: Selection::default(),
+    },
+    world.dir.path(),
+  );
+}
+
+#[when("fixing with backups")]
+fn fixing_with_backups(world: &mut MyWorld) {
+  world.output = tikibase::run(
+    Command::Fix {
+      backup: true,
+      interactive: false,
+      selection: Selection::default(),
+    },
+    world.dir.path(),
+  );
+}
+
+#[when(expr = "running {string}")]
+fn running(world: &mut MyWorld, call: String) {
+  let args = Arguments::parse_from(call.split(' '));
+  world.output = tikibase::run(args.command, world.dir.path());
+}
+
+#[when(expr = "file {string} gets changed to:")]
+fn file_gets_changed(world: &mut MyWorld, step: &Step, filename: String) {
+  let content = step.docstring.as_ref().unwrap().trim();
+  test::create_file(&filename, content, world.dir.path());
+}
+
+#[when("undoing")]
+fn undoing(world: &mut MyWorld) {
+  world.output = tikibase::run(Command::Undo, world.dir.path());
 }
 
 #[when(expr = "I run {string}")]
@@ -78,6 +156,30 @@ fn i_run(world: &mut MyWorld, call: String) {
   );
 }
 
+#[when(expr = "I run {string} and answer {string}")]
+fn i_run_and_answer(world: &mut MyWorld, call: String, answers: String) {
+  let mut args = call.split(" ");
+  let executable = args.next().unwrap();
+  if executable != "tikibase" {
+    panic!("can only test tikibase");
+  }
+  let cwd = std::env::current_dir().unwrap();
+  let mut child = std::process::Command::new(cwd.join("target/release/tikibase"))
+    .args(args)
+    .current_dir(world.dir.path())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  let mut stdin = child.stdin.take().unwrap();
+  for answer in answers.split(',') {
+    writeln!(stdin, "{}", answer.trim()).unwrap();
+  }
+  drop(stdin);
+  world.subshell_output = Some(child.wait_with_output().unwrap());
+}
+
 #[when("initializing")]
 fn initializing(world: &mut MyWorld) {
   world.result = tikibase::commands::init(world.dir.path());
@@ -105,9 +207,32 @@ fn file_should_contain(world: &mut MyWorld, step: &Step, filename: String) {
   pretty::assert_eq!(have.trim(), want.trim());
 }
 
+#[then(expr = "file {string} should contain Latin-1:")]
+fn file_should_contain_latin1(world: &mut MyWorld, step: &Step, filename: String) {
+  let bytes = fs_err::read(world.dir.path().join(&filename)).unwrap();
+  let have: String = bytes.into_iter().map(char::from).collect();
+  let want = step.docstring.as_ref().unwrap();
+  pretty::assert_eq!(have.trim(), want.trim());
+}
+
+#[then(expr = "the backup of file {string} contains:")]
+fn backup_contains(world: &mut MyWorld, step: &Step, filename: String) {
+  let backup_root = world.dir.path().join(".tikibase/backup");
+  let mut backups = backup_root.read_dir_utf8().unwrap();
+  let backup_dir = backups.next().unwrap().unwrap();
+  assert!(backups.next().is_none(), "multiple backups");
+  let have = test::load_file(&filename, backup_dir.path());
+  let want = step.docstring.as_ref().unwrap();
+  pretty::assert_eq!(have.trim(), want.trim());
+}
+
 #[then("it prints:")]
 fn it_prints(world: &mut MyWorld, step: &Step) {
   let mut have = S("");
+  for message in &world.output.results {
+    have.push_str(message.to_text().trim());
+    have.push('\n');
+  }
   for message in &world.output.issues {
     have.push_str(message.to_text().trim());
     have.push('\n');